@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 use crate::misc::version::SemanticVersion;
 
@@ -33,12 +33,20 @@ pub enum PythonSubCommands {
     VirtualEnv(VirtualEnvOption),
     #[command(about = "Fix Virtual Environments", name = "fix-venv")]
     FixVirtualEnvironments(FixVirtualEnvOption),
+    #[command(about = "Relocate Virtual Environment", name = "relocate-venv")]
+    RelocateVirtualEnv(RelocateVirtualEnvOption),
     #[command(about = "Execute Command To Virtual Environments", name = "execute")]
     VirtualEnvExecute(VirtualEnvExecuteOption),
     #[command(about = "Virtual Environment Packages", name = "packages")]
     EnvPackages(PackagesOption),
+    #[command(about = "Compare Virtual Environment Packages", name = "diff-envs")]
+    DiffEnvs(DiffEnvsOption),
     #[command(about = "Python Download", name = "download")]
     PythonDownload(PythonDownloadOption),
+    #[command(about = "List Available Python Releases", name = "list-remote")]
+    ListRemote(ListRemoteOption),
+    #[command(about = "Build And Install Python From Source", name = "install")]
+    PythonInstall(PythonInstallOption),
 }
 
 #[derive(Debug, Parser)]
@@ -51,21 +59,127 @@ pub struct PythonDownloadOption {
     #[arg(short = 'R', long = "recent-patch", default_value = "false")]
     pub recent_patch: bool,
 
+    /// Include pre-releases [e.g. 3.13.0rc1]
+    #[arg(long = "pre", default_value = "false")]
+    pub pre: bool,
+
     /// List Python version files [No Download]
     #[arg(short = 'L', long = "list", default_value = "false")]
     pub list_structure: bool,
 
-    /// Specify Architecture: [amd64, arm64, n/a]
+    /// Specify Architecture: [amd64, arm64, aarch64, x86_64, n/a]
     #[arg(short = 'A', long = "arch", default_value = "amd64")]
     pub architecture: String,
 
-    /// Specify Platform: [windows, macos, any]
+    /// Specify Platform: [windows, macos, android, ios, any]
     #[arg(short = 'P', long = "platform", default_value = "windows")]
     pub platform: String,
 
-    /// Specify Package Type: [standard, webinstall, embed, source]
+    /// Specify Package Type: [standard, webinstall, embed, nuget, source]
     #[arg(short = 'T', long = "package-type", default_value = "standard")]
     pub package_type: String,
+
+    /// Specify File Extension: [exe, msi, pkg, dmg, zip, nupkg, tar.gz, tgz, tar.xz, tar.bz2] [Default: tar.xz before tgz on Linux]
+    #[arg(short = 'X', long = "extension")]
+    pub extension: Option<String>,
+
+    /// Fail unless the download matches its published SHA-256 digest
+    #[arg(long = "verify", default_value = "false")]
+    pub verify: bool,
+
+    /// Save the download into this directory
+    #[arg(long = "output-dir", conflicts_with = "output")]
+    pub output_dir: Option<String>,
+
+    /// Save the download to this file path
+    #[arg(short = 'O', long = "output")]
+    pub output: Option<String>,
+
+    /// When the destination exists: [fail, overwrite, skip (if verified)]
+    #[arg(
+        short = 'E',
+        long = "if-exists",
+        default_value = "fail",
+        value_parser = ["fail", "overwrite", "skip"]
+    )]
+    pub if_exists: String,
+
+    /// Bypass the shared download cache
+    #[arg(short = 'N', long = "no-cache", default_value = "false")]
+    pub no_cache: bool,
+
+    /// Download from a mirror URL or local directory [Overrides python_mirror setting]
+    #[arg(short = 'M', long = "mirror")]
+    pub mirror: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct PythonInstallOption {
+    /// Python version to install [e.g. 3.12.4, or 3.12 with -R]
+    pub version: SemanticVersion,
+
+    /// Install the most recent patch
+    #[arg(short = 'R', long = "recent-patch", default_value = "false")]
+    pub recent_patch: bool,
+
+    /// Include pre-releases [e.g. 3.13.0rc1]
+    #[arg(long = "pre", default_value = "false")]
+    pub pre: bool,
+
+    /// Continue when the source archive cannot be verified against a published SHA-256 digest
+    #[arg(short = 'U', long = "unverified", default_value = "false")]
+    pub unverified: bool,
+
+    /// Build with profile guided optimizations [Slower build]
+    #[arg(long = "optimize", default_value = "false")]
+    pub optimize: bool,
+
+    /// Number of parallel make jobs [Default: available CPUs]
+    #[arg(short = 'J', long = "jobs", value_parser = parse_search_option_limit)]
+    pub jobs: Option<usize>,
+
+    /// Reinstall when the same version is already installed
+    #[arg(short = 'F', long = "force", default_value = "false")]
+    pub force: bool,
+
+    /// Bypass the shared download cache
+    #[arg(short = 'N', long = "no-cache", default_value = "false")]
+    pub no_cache: bool,
+
+    /// Download from a mirror URL or local directory [Overrides python_mirror setting]
+    #[arg(short = 'M', long = "mirror")]
+    pub mirror: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct ListRemoteOption {
+    /// Only list releases of this major or major.minor version [e.g. 3, 3.12]
+    #[arg(short = 'V', long = "version", value_parser = parse_release_filter)]
+    pub version: Option<String>,
+
+    /// Include pre-releases [e.g. 3.13.0rc1]
+    #[arg(long = "pre", default_value = "false")]
+    pub pre: bool,
+
+    /// Only list artifacts of this Architecture: [amd64, arm64, aarch64, x86_64, n/a]
+    #[arg(short = 'A', long = "arch")]
+    pub architecture: Option<String>,
+
+    /// Only list artifacts of this Platform: [windows, macos, android, ios, any]
+    #[arg(short = 'P', long = "platform")]
+    pub platform: Option<String>,
+
+    /// Only list artifacts of this Package Type: [standard, webinstall, embed, nuget, source]
+    #[arg(short = 'T', long = "package-type")]
+    pub package_type: Option<String>,
+
+    /// Number of releases to list, newest first
+    #[arg(short = 'L', long = "limit", default_value = "10", value_parser = parse_search_option_limit)]
+    pub limit: usize,
+
+    /// List releases of a mirror URL or local directory [Overrides python_mirror setting]
+    #[arg(short = 'M', long = "mirror")]
+    pub mirror: Option<String>,
 }
 
 #[derive(Debug, Parser)]
@@ -75,22 +189,74 @@ pub struct VirtualEnvOption {
     pub version: SemanticVersion,
 }
 
-#[derive(Debug, Parser)]
-pub struct FixVirtualEnvOption {
+/// Virtual Environment Selection
+#[derive(Debug, Args)]
+pub struct VirtualEnvSelectOption {
     /// Perform a deep search
     #[arg(short = 'D', long = "deep-search")]
     pub deep_search: bool,
+
+    /// Only select environments of this Python version [e.g. 3.11]
+    #[arg(short = 'P', long = "python")]
+    pub python: Option<String>,
+
+    /// Only select environments whose path matches the glob
+    #[arg(short = 'G', long = "path-glob")]
+    pub path_glob: Option<String>,
+
+    /// Skip environments whose path matches the glob [Repeatable]
+    #[arg(short = 'E', long = "exclude")]
+    pub exclude: Vec<String>,
+
+    /// Pick environments from a numbered list
+    #[arg(short = 'I', long = "interactive", conflicts_with = "yes")]
+    pub interactive: bool,
+
+    /// Continue without asking for confirmation
+    #[arg(short = 'Y', long = "yes")]
+    pub yes: bool,
 }
 
 #[derive(Debug, Parser)]
-pub struct VirtualEnvExecuteOption {
-    /// Perform a deep search
-    #[arg(short = 'D', long = "deep-search")]
-    pub deep_search: bool,
+pub struct FixVirtualEnvOption {
+    #[command(flatten)]
+    pub selection: VirtualEnvSelectOption,
 
-    /// Pass command to each virtual environment
-    #[arg(short = 'C', long = "command", raw(true))]
-    pub command: String,
+    /// Diagnose environments without repairing them
+    #[arg(short = 'N', long = "dry-run", default_value = "false")]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct RelocateVirtualEnvOption {
+    /// Path of the virtual environment to move
+    pub source: String,
+
+    /// Path to move the virtual environment to
+    pub destination: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct VirtualEnvExecuteOption {
+    #[command(flatten)]
+    pub selection: VirtualEnvSelectOption,
+
+    /// Pass command to each virtual environment [Supports shell-like quoting]
+    #[arg(
+        short = 'C',
+        long = "command",
+        allow_hyphen_values = true,
+        required_unless_present = "args"
+    )]
+    pub command: Option<String>,
+
+    /// Number of environments to execute concurrently
+    #[arg(short = 'J', long = "jobs", default_value = "1", value_parser = parse_search_option_limit)]
+    pub jobs: usize,
+
+    /// Arguments passed verbatim to each virtual environment [After --]
+    #[arg(last = true)]
+    pub args: Vec<String>,
 }
 
 /// Virtual Environment Packages
@@ -98,17 +264,65 @@ pub struct VirtualEnvExecuteOption {
 /// [$ENV placeholder refers to the root path of a Python Virtual Environment]
 #[derive(Debug, Parser)]
 pub struct PackagesOption {
-    /// Perform a deep search
-    #[arg(short = 'D', long = "deep-search")]
-    pub deep_search: bool,
+    #[command(flatten)]
+    pub selection: VirtualEnvSelectOption,
 
     /// Save package list for each environment [$ENV/packages.txt]
     #[arg(short = 'S', long = "save", default_value = "false")]
     pub save: bool,
 
-    /// Distill packages by mutual dependencies [With -S: $ENV/distilled_packages.txt]
+    /// Format of the saved package list
+    #[arg(
+        short = 'F',
+        long = "format",
+        default_value = "requirements",
+        value_parser = ["requirements", "json", "pyproject", "constraints"]
+    )]
+    pub format: String,
+
+    /// Path of the saved package list, implies --save [Relative to $ENV]
+    #[arg(short = 'O', long = "output")]
+    pub output: Option<String>,
+
+    /// Distill to the top-level packages that reproduce the environment [With -S: $ENV/distilled_packages.txt]
     #[arg(short = 'X', long = "distill", default_value = "false")]
     pub distill: bool,
+
+    /// Show dependency tree of installed packages
+    #[arg(
+        short = 'T',
+        long = "tree",
+        default_value = "false",
+        conflicts_with = "distill"
+    )]
+    pub tree: bool,
+
+    /// Show which packages depend on a package
+    #[arg(short = 'R', long = "reverse", conflicts_with_all = ["tree", "distill"])]
+    pub reverse: Option<String>,
+
+    /// Check installed packages for broken requirements [Exit code 1 on failure]
+    #[arg(
+        short = 'C',
+        long = "check",
+        default_value = "false",
+        conflicts_with_all = ["tree", "reverse", "distill"]
+    )]
+    pub check: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct DiffEnvsOption {
+    #[command(flatten)]
+    pub selection: VirtualEnvSelectOption,
+
+    /// Compare each environment against a requirements file
+    #[arg(short = 'A', long = "against")]
+    pub against: Option<String>,
+
+    /// Include packages that are identical in every environment
+    #[arg(short = 'U', long = "unchanged", default_value = "false")]
+    pub unchanged: bool,
 }
 
 #[derive(Debug, Parser)]
@@ -169,6 +383,17 @@ fn parse_search_option_limit(value: &str) -> Result<usize, &'static str> {
     }
 }
 
+fn parse_release_filter(value: &str) -> Result<String, &'static str> {
+    let parts: Vec<&str> = value.split('.').collect();
+    let is_numeric: bool = parts
+        .iter()
+        .all(|part| !part.is_empty() && part.chars().all(|char| char.is_ascii_digit()));
+    if parts.len() > 2 || !is_numeric {
+        return Err("expected a major or major.minor version [e.g. 3, 3.12]");
+    }
+    Ok(value.to_string())
+}
+
 #[derive(Debug, Clone)]
 pub enum SearchSort {
     SizeAscending,
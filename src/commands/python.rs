@@ -1,33 +1,116 @@
+use std::cmp::Ordering;
 use std::error::Error;
-use std::fs::File;
+use std::fs;
 use std::io;
-use std::io::Write;
-use std::path::PathBuf;
-use std::str::SplitWhitespace;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::process::ExitStatus;
+use std::thread;
+use std::time::Instant;
 
 use dirs;
+use thread_manager::ThreadManager;
 
 use crate::terminal::Terminal;
-use crate::terminal::{GreenANSI, RedANSI, YellowANSI};
+use crate::terminal::{CyanANSI, GreenANSI, RedANSI, YellowANSI};
 
+use crate::commands::config::DiffEnvsOption;
 use crate::commands::config::FixVirtualEnvOption;
+use crate::commands::config::ListRemoteOption;
 use crate::commands::config::PackagesOption;
 use crate::commands::config::PythonDownloadOption;
+use crate::commands::config::PythonInstallOption;
+use crate::commands::config::RelocateVirtualEnvOption;
 use crate::commands::config::VirtualEnvExecuteOption;
 use crate::commands::config::VirtualEnvOption;
+use crate::commands::config::VirtualEnvSelectOption;
 
-use crate::misc::https::HTTPS;
+use crate::misc::cache::DownloadCache;
+use crate::misc::https::{HTTPSError, HTTPS};
 use crate::misc::path::WPath;
-use crate::misc::table_display::DynamicTable;
+use crate::misc::shell::{CommandExecute, CommandResponse};
+use crate::misc::table_display::{DynamicTable, GridTable};
 use crate::misc::version::SemanticVersion;
 
-use crate::languages::python::ftp::PythonFTPRetriever;
-use crate::languages::python::pip::{PipMetadata, PipPackage, PipPackageParser};
+use crate::parsers::shell_parser::ShellParser;
+
+use crate::languages::python::comparison::PipComparison;
+use crate::languages::python::dependency::{PipConflict, PipDependencyGraph};
+use crate::languages::python::diagnosis::VirtualEnvDiagnosis;
+use crate::languages::python::dist_info::PipDistInfo;
+use crate::languages::python::export::{PipExportFormat, PipExporter};
+use crate::languages::python::ftp::{PythonFTPRetriever, PythonFilename};
+use crate::languages::python::pip::{PipMetadata, PipPackage, PipPackageName};
+use crate::languages::python::pip::{PipPackageParser, PipRequirementsParser};
 use crate::languages::python::python::PythonEnvironment;
+use crate::languages::python::relocate::VirtualEnvRelocator;
+use crate::languages::python::verify::{PythonArtifactVerifier, PythonVerification};
 use crate::languages::python::virtualenv::VirtualEnv;
 use crate::languages::python::virtualenv::VirtualEnvCFG;
+use crate::languages::python::virtualenv::VirtualEnvExecution;
 use crate::languages::python::virtualenv::VirtualEnvSearch;
 
+use crate::search::formatters::{format_size, format_time};
+
+struct VirtualEnvSelector<'a> {
+    option: &'a VirtualEnvSelectOption,
+    terminal: Terminal,
+}
+
+impl<'a> VirtualEnvSelector<'a> {
+    fn new(option: &'a VirtualEnvSelectOption) -> Self {
+        let terminal: Terminal = Terminal::new();
+        VirtualEnvSelector { option, terminal }
+    }
+
+    fn find_configs(&self) -> Vec<VirtualEnvCFG> {
+        let venv_search: Result<VirtualEnvSearch, regex::Error> = self.get_venv_search();
+
+        match venv_search {
+            Ok(venv_search) => venv_search.find_configs(),
+            Err(error) => {
+                let error_string: String = format!("Error: Invalid glob pattern: {}", error);
+                self.terminal.writeln_ansi(&error_string, &RedANSI);
+                Vec::new()
+            }
+        }
+    }
+
+    fn add_parameters(&self, table: &mut DynamicTable) {
+        table.add_fmt_parameter("Deep Search", self.option.deep_search);
+
+        if let Some(python) = &self.option.python {
+            table.add_fmt_parameter("Python", python);
+        }
+        if let Some(path_glob) = &self.option.path_glob {
+            table.add_fmt_parameter("Path Glob", path_glob);
+        }
+        if !self.option.exclude.is_empty() {
+            table.add_fmt_parameter("Exclude", &self.option.exclude);
+        }
+        if self.option.interactive {
+            table.add_fmt_parameter("Interactive", self.option.interactive);
+        }
+    }
+
+    fn get_venv_search(&self) -> Result<VirtualEnvSearch, regex::Error> {
+        let mut venv_search: VirtualEnvSearch = VirtualEnvSearch::new(self.option.deep_search);
+        venv_search.set_interactive(self.option.interactive);
+        venv_search.set_assume_yes(self.option.yes);
+
+        if let Some(python) = &self.option.python {
+            venv_search.set_python_version(python);
+        }
+        if let Some(path_glob) = &self.option.path_glob {
+            venv_search.set_path_glob(path_glob)?;
+        }
+        for exclude in self.option.exclude.iter() {
+            venv_search.add_exclude_glob(exclude)?;
+        }
+        Ok(venv_search)
+    }
+}
+
 pub struct PythonCreateEnvCommand {
     option: VirtualEnvOption,
 }
@@ -69,23 +152,53 @@ impl PythonFixEnvCommand {
     }
 
     pub fn execute_command(&self) {
-        let deep_search: bool = self.option.deep_search;
         self.print_search_parameters();
 
-        let venv_search: VirtualEnvSearch = VirtualEnvSearch::new(deep_search);
-        let venv_cfgs: Vec<VirtualEnvCFG> = venv_search.find_configs();
+        let venv_selector: VirtualEnvSelector = VirtualEnvSelector::new(&self.option.selection);
+        let venv_cfgs: Vec<VirtualEnvCFG> = venv_selector.find_configs();
+        if venv_cfgs.is_empty() {
+            return;
+        }
+
+        let mut broken_cfgs: Vec<VirtualEnvCFG> = Vec::new();
 
         for venv_cfg in venv_cfgs {
-            let mut environment_directory: WPath = venv_cfg.cfg_file.clone().into();
-            environment_directory.to_directory();
+            let diagnosis: VirtualEnvDiagnosis = VirtualEnvDiagnosis::new(&venv_cfg);
+            diagnosis.print();
+            println!();
+
+            if diagnosis.needs_repair() {
+                broken_cfgs.push(venv_cfg);
+            }
+        }
+
+        if broken_cfgs.is_empty() {
+            let string: &str = "All environments are healthy.";
+            self.terminal.writeln_ansi(string, &GreenANSI);
+            return;
+        }
+
+        if self.option.dry_run {
+            let count: String = broken_cfgs.len().to_string();
+            let parts: [&str; 2] = ["[Dry Run] Environments To Repair: ", &count];
+            self.terminal.writeln_parameter(&parts, &YellowANSI);
+            return;
+        }
+
+        for venv_cfg in broken_cfgs {
+            let environment_directory: WPath = venv_cfg.get_environment_directory();
 
             let directory_string: String = format!("{:?}", environment_directory);
             let parts: [&str; 2] = ["Attempting Environment Fix: ", &directory_string];
             self.terminal.writeln_parameter(&parts, &YellowANSI);
 
-            let version: SemanticVersion = venv_cfg.version_info;
-            let cfg_file: WPath = venv_cfg.cfg_file;
-            self.create_env(&cfg_file, &version);
+            let version: Option<SemanticVersion> = venv_cfg.get_version();
+            if let Some(version) = version {
+                self.create_env(&venv_cfg.cfg_file, &version);
+            } else {
+                let string: &str = "Unable to determine the Python version of the environment.";
+                self.terminal.writeln_ansi(string, &RedANSI);
+            }
             println!();
         }
     }
@@ -112,10 +225,52 @@ impl PythonFixEnvCommand {
     }
 
     fn print_search_parameters(&self) {
-        let deep_search: bool = self.option.deep_search;
+        let venv_selector: VirtualEnvSelector = VirtualEnvSelector::new(&self.option.selection);
+        let dry_run: bool = self.option.dry_run;
         let mut table = DynamicTable::new(0.6, 1);
         table.set_header("Fix Venv Parameters");
-        table.add_fmt_parameter("Deep Search", deep_search);
+        venv_selector.add_parameters(&mut table);
+        table.add_fmt_parameter("Dry Run", dry_run);
+        table.print();
+        println!();
+    }
+}
+
+pub struct PythonRelocateEnvCommand {
+    option: RelocateVirtualEnvOption,
+    terminal: Terminal,
+}
+
+impl PythonRelocateEnvCommand {
+    pub fn new(option: RelocateVirtualEnvOption) -> Self {
+        let terminal: Terminal = Terminal::new();
+        PythonRelocateEnvCommand { option, terminal }
+    }
+
+    pub fn execute_command(&self) {
+        self.print_relocate_parameters();
+
+        let source: WPath = WPath::from_string(&self.option.source);
+        let destination: WPath = WPath::from_string(&self.option.destination);
+        let relocator: VirtualEnvRelocator = VirtualEnvRelocator::new(&source, &destination);
+
+        if let Err(error) = relocator.relocate() {
+            let error: String = error.to_string();
+            let parts: [&str; 2] = ["Error: ", &error];
+            self.terminal.writeln_parameter(&parts, &RedANSI);
+        }
+    }
+}
+
+impl PythonRelocateEnvCommand {
+    fn print_relocate_parameters(&self) {
+        let source: &String = &self.option.source;
+        let destination: &String = &self.option.destination;
+
+        let mut table: DynamicTable = DynamicTable::new(0.6, 1);
+        table.set_header("Relocate Parameters");
+        table.add_fmt_parameter("Source", source);
+        table.add_fmt_parameter("Destination", destination);
         table.print();
         println!();
     }
@@ -133,53 +288,161 @@ impl PythonExecuteCommand {
     }
 
     pub fn execute_command(&self) {
-        let deep_search: bool = self.option.deep_search;
+        let jobs: usize = self.option.jobs;
         self.print_search_parameters();
 
-        let args: Vec<&str> = self.get_command_args();
-        let venv_search: VirtualEnvSearch = VirtualEnvSearch::new(deep_search);
-        let venv_cfgs: Vec<VirtualEnvCFG> = venv_search.find_configs();
+        let args: Result<Vec<String>, io::Error> = self.get_command_args();
+        if let Err(error) = args {
+            let error_string: String = format!("Error: {}", error);
+            self.terminal.writeln_ansi(&error_string, &RedANSI);
+            return;
+        }
+        let args: Vec<String> = args.unwrap();
+
+        let venv_selector: VirtualEnvSelector = VirtualEnvSelector::new(&self.option.selection);
+        let venv_cfgs: Vec<VirtualEnvCFG> = venv_selector.find_configs();
+
+        let executions: Vec<VirtualEnvExecution> = if jobs > 1 {
+            self.execute_parallel(&venv_cfgs, &args, jobs)
+        } else {
+            self.execute_sequential(&venv_cfgs, &args)
+        };
+
+        if !executions.is_empty() {
+            println!();
+            self.print_summary(&executions);
+        }
+    }
+}
+
+impl PythonExecuteCommand {
+    fn execute_sequential(
+        &self,
+        venv_cfgs: &[VirtualEnvCFG],
+        args: &[String],
+    ) -> Vec<VirtualEnvExecution> {
+        let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+        let mut executions: Vec<VirtualEnvExecution> = Vec::new();
 
         for (idx, venv_cfg) in venv_cfgs.iter().enumerate() {
             let env_directory: WPath = venv_cfg.get_environment_directory();
-            let python_executable: WPath = venv_cfg.get_python_executable();
-            let version: &SemanticVersion = &venv_cfg.version_info;
-            let environment: Option<PythonEnvironment> =
-                PythonEnvironment::from_custom_path(&env_directory, &python_executable, version);
+            let environment: Option<PythonEnvironment> = Self::get_environment(venv_cfg);
+
+            let start: Instant = Instant::now();
+            let mut status: Option<ExitStatus> = None;
 
             if let Some(environment) = environment {
                 let string: String = format!("[Environment -> {:?}]", env_directory);
                 self.terminal.writeln_ansi(&string, &YellowANSI);
 
                 let virtual_env: VirtualEnv = VirtualEnv::new(&environment);
-                virtual_env.execute_custom_command(&args);
+                status = virtual_env.execute_custom_command(&args);
             }
 
+            let execution: VirtualEnvExecution =
+                VirtualEnvExecution::new(&env_directory, status, None, start.elapsed());
+            executions.push(execution);
+
             if idx != (venv_cfgs.len() - 1) {
                 println!()
             }
         }
+        executions
     }
-}
 
-impl PythonExecuteCommand {
-    fn get_command_args(&self) -> Vec<&str> {
-        let command: &str = &self.option.command;
-        let split: SplitWhitespace = command.split_whitespace();
-        let args: Vec<&str> = split.into_iter().collect();
-        args
+    fn execute_parallel(
+        &self,
+        venv_cfgs: &[VirtualEnvCFG],
+        args: &[String],
+        jobs: usize,
+    ) -> Vec<VirtualEnvExecution> {
+        let thread_manager: ThreadManager<VirtualEnvExecution> = ThreadManager::new(jobs);
+        let mut executions: Vec<VirtualEnvExecution> = Vec::new();
+
+        for venv_cfg in venv_cfgs.iter() {
+            let venv_cfg: VirtualEnvCFG = venv_cfg.clone();
+            let args: Vec<String> = args.to_vec();
+            thread_manager.execute(move || Self::execute_buffered(&venv_cfg, &args));
+        }
+
+        for execution in thread_manager.yield_results() {
+            execution.print_block();
+            executions.push(execution);
+        }
+        thread_manager.join();
+
+        executions.sort_by_key(|execution| format!("{:?}", execution.get_environment_directory()));
+        executions
+    }
+
+    fn execute_buffered(venv_cfg: &VirtualEnvCFG, args: &[String]) -> VirtualEnvExecution {
+        let env_directory: WPath = venv_cfg.get_environment_directory();
+        let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+        let start: Instant = Instant::now();
+
+        let environment: Option<PythonEnvironment> = Self::get_environment(venv_cfg);
+        let response: Option<CommandResponse> = environment.and_then(|environment| {
+            let virtual_env: VirtualEnv = VirtualEnv::new(&environment);
+            virtual_env.execute_buffered_command(&args)
+        });
+        VirtualEnvExecution::new(&env_directory, None, response, start.elapsed())
+    }
+
+    fn get_environment(venv_cfg: &VirtualEnvCFG) -> Option<PythonEnvironment> {
+        let env_directory: WPath = venv_cfg.get_environment_directory();
+        let python_executable: WPath = venv_cfg.get_python_executable();
+        let version: Option<SemanticVersion> = venv_cfg.get_version();
+        version.and_then(|version| {
+            PythonEnvironment::from_custom_path(&env_directory, &python_executable, &version)
+        })
+    }
+
+    fn get_command_args(&self) -> Result<Vec<String>, io::Error> {
+        let mut args: Vec<String> = Vec::new();
+        if let Some(command) = &self.option.command {
+            let shell_parser: ShellParser = ShellParser::new();
+            args = shell_parser.tokenize(command)?;
+        }
+        args.extend(self.option.args.iter().cloned());
+        Ok(args)
     }
+
     fn print_search_parameters(&self) {
-        let deep_search: bool = self.option.deep_search;
-        let command: &String = &self.option.command;
+        let venv_selector: VirtualEnvSelector = VirtualEnvSelector::new(&self.option.selection);
+        let command: &Option<String> = &self.option.command;
+        let args: &Vec<String> = &self.option.args;
+        let jobs: usize = self.option.jobs;
 
         let mut table: DynamicTable = DynamicTable::new(0.6, 1);
         table.set_header("Execute Parameters");
-        table.add_fmt_parameter("Deep Search", deep_search);
-        table.add_fmt_parameter("Command", command);
+        venv_selector.add_parameters(&mut table);
+        if let Some(command) = command {
+            table.add_fmt_parameter("Command", command);
+        }
+        if !args.is_empty() {
+            table.add_fmt_parameter("Arguments", args);
+        }
+        table.add_fmt_parameter("Jobs", jobs);
         table.print();
         println!();
     }
+
+    fn print_summary(&self, executions: &[VirtualEnvExecution]) {
+        let mut table: DynamicTable = DynamicTable::new(0.8, 1);
+        table.set_header("Execute Summary");
+
+        for execution in executions.iter() {
+            let exit_code: String = match execution.get_exit_code() {
+                Some(exit_code) => exit_code.to_string(),
+                None => "-".to_string(),
+            };
+            let duration: String = format_time(execution.get_duration().as_nanos());
+            let value: String = format!("Exit: {} | Duration: {}", exit_code, duration);
+            let attribute: String = format!("{:?}", execution.get_environment_directory());
+            table.add_string_parameter(attribute, value);
+        }
+        table.print();
+    }
 }
 
 pub struct PythonPackagesCommand {
@@ -194,25 +457,31 @@ impl PythonPackagesCommand {
     }
 
     pub fn execute_command(&self) {
-        let deep_search: bool = self.option.deep_search;
-        let save: bool = self.option.save;
+        let save: bool = self.option.save || self.option.output.is_some();
 
         self.print_search_parameters();
 
-        let venv_search: VirtualEnvSearch = VirtualEnvSearch::new(deep_search);
-        let venv_cfgs: Vec<VirtualEnvCFG> = venv_search.find_configs();
+        let venv_selector: VirtualEnvSelector = VirtualEnvSelector::new(&self.option.selection);
+        let venv_cfgs: Vec<VirtualEnvCFG> = venv_selector.find_configs();
+        let mut is_broken: bool = false;
 
         for (idx, venv_cfg) in venv_cfgs.iter().enumerate() {
             let env_dir: WPath = venv_cfg.get_environment_directory();
 
             let packages: Result<Vec<PipPackage>, io::Error> =
-                self.get_packages_from_option(&env_dir);
+                self.get_packages_from_option(venv_cfg);
 
             if let Ok(packages) = packages {
                 let string: String = format!("[Environment -> {:?}]", env_dir);
                 self.terminal.writeln_ansi(&string, &YellowANSI);
 
-                self.list_packages(&packages);
+                if self.option.check {
+                    is_broken |= !self.check_packages(venv_cfg, &packages);
+                } else if self.option.tree || self.option.reverse.is_some() {
+                    self.print_dependencies(venv_cfg, &packages);
+                } else {
+                    self.list_packages(&packages);
+                }
 
                 if save {
                     self.save_packages(&env_dir, &packages);
@@ -223,56 +492,82 @@ impl PythonPackagesCommand {
                 }
             }
         }
+
+        if is_broken {
+            process::exit(1);
+        }
     }
 }
 
 impl PythonPackagesCommand {
     fn print_search_parameters(&self) {
-        let deep_search: bool = self.option.deep_search;
-        let save: bool = self.option.save;
+        let venv_selector: VirtualEnvSelector = VirtualEnvSelector::new(&self.option.selection);
+        let save: bool = self.option.save || self.option.output.is_some();
         let distill: bool = self.option.distill;
 
         let mut table: DynamicTable = DynamicTable::new(0.6, 1);
         table.set_header("Packages Parameters");
-        table.add_fmt_parameter("Deep Search", deep_search);
+        venv_selector.add_parameters(&mut table);
         table.add_fmt_parameter("Distill", distill);
         table.add_fmt_parameter("Save", save);
+        if save {
+            table.add_string_parameter("Format", self.get_export_format().get_string());
+        }
+        if let Some(output) = &self.option.output {
+            table.add_fmt_parameter("Output", output);
+        }
+        table.add_fmt_parameter("Tree", self.option.tree);
+        table.add_fmt_parameter("Check", self.option.check);
+        if let Some(reverse) = &self.option.reverse {
+            table.add_string_parameter("Reverse", reverse);
+        }
         table.print();
         println!();
     }
 
-    fn get_filename_from_option(&self) -> &str {
-        let distill: bool = self.option.distill;
-        let filename: &str = if distill {
-            "distilled_packages.txt"
-        } else {
-            "packages.txt"
-        };
-        filename
+    fn get_export_format(&self) -> PipExportFormat {
+        let format: Option<PipExportFormat> = PipExportFormat::from_string(&self.option.format);
+        format.unwrap_or(PipExportFormat::Requirements)
+    }
+
+    fn get_output_path(&self, env_dir: &WPath) -> WPath {
+        let format: PipExportFormat = self.get_export_format();
+        let filename: &str = format.get_default_filename(self.option.distill);
+        let output: &str = self.option.output.as_deref().unwrap_or(filename);
+
+        let output_path: WPath = WPath::from_string(output);
+        if output_path.get_path_buf().is_absolute() {
+            return output_path;
+        }
+        env_dir.join(output)
     }
 
-    fn get_packages_from_option(&self, env_dir: &WPath) -> Result<Vec<PipPackage>, io::Error> {
+    fn get_packages_from_option(
+        &self,
+        venv_cfg: &VirtualEnvCFG,
+    ) -> Result<Vec<PipPackage>, io::Error> {
         let distill: bool = self.option.distill;
         let packages: Result<Vec<PipPackage>, io::Error> = if distill {
-            self.get_distilled_packages(&env_dir)
+            self.get_distilled_packages(venv_cfg)
         } else {
-            self.get_packages(&env_dir)
+            self.get_packages(venv_cfg)
         };
         packages
     }
 
-    fn get_packages(&self, env_dir: &WPath) -> Result<Vec<PipPackage>, io::Error> {
-        let packages_dir: WPath = env_dir.join("Lib/site-packages/");
-        let package_parser: PipPackageParser = PipPackageParser::new(&packages_dir);
+    fn get_packages(&self, venv_cfg: &VirtualEnvCFG) -> Result<Vec<PipPackage>, io::Error> {
+        let package_parser: PipPackageParser = self.get_package_parser(venv_cfg);
 
         let packages: Vec<PipPackage> = package_parser.get_packages()?;
 
         Ok(packages)
     }
 
-    fn get_distilled_packages(&self, env_dir: &WPath) -> Result<Vec<PipPackage>, io::Error> {
-        let packages_dir: WPath = env_dir.join("Lib/site-packages/");
-        let package_parser: PipPackageParser = PipPackageParser::new(&packages_dir);
+    fn get_distilled_packages(
+        &self,
+        venv_cfg: &VirtualEnvCFG,
+    ) -> Result<Vec<PipPackage>, io::Error> {
+        let package_parser: PipPackageParser = self.get_package_parser(venv_cfg);
 
         let mut packages: Vec<PipPackage> = package_parser.get_packages()?;
         let metadata: Vec<PipMetadata> = package_parser.get_metadata(&packages);
@@ -281,10 +576,54 @@ impl PythonPackagesCommand {
         Ok(packages)
     }
 
+    fn get_package_parser(&self, venv_cfg: &VirtualEnvCFG) -> PipPackageParser {
+        let packages_dir: WPath = venv_cfg.get_site_packages_directory();
+        let mut package_parser: PipPackageParser = PipPackageParser::new(&packages_dir);
+
+        let version: Option<SemanticVersion> = venv_cfg.get_version();
+        if let Some(version) = version {
+            package_parser.set_python_version(&version);
+        }
+        package_parser
+    }
+
+    fn print_dependencies(&self, venv_cfg: &VirtualEnvCFG, packages: &[PipPackage]) {
+        let package_parser: PipPackageParser = self.get_package_parser(venv_cfg);
+        let metadata: Vec<PipMetadata> = package_parser.get_metadata(packages);
+        let graph: PipDependencyGraph = PipDependencyGraph::new(packages, &metadata);
+
+        match &self.option.reverse {
+            Some(reverse) => graph.print_reverse(&PipPackageName::new(reverse)),
+            None => graph.print_tree(),
+        }
+    }
+
+    /// Reports broken requirements and returns whether the environment is consistent.
+    fn check_packages(&self, venv_cfg: &VirtualEnvCFG, packages: &[PipPackage]) -> bool {
+        let package_parser: PipPackageParser = self.get_package_parser(venv_cfg);
+        let metadata: Vec<PipMetadata> = package_parser.get_metadata(packages);
+        let graph: PipDependencyGraph = PipDependencyGraph::new(packages, &metadata);
+        let conflicts: Vec<PipConflict> = graph.get_conflicts();
+
+        for conflict in conflicts.iter() {
+            let conflict_string: String = conflict.get_string();
+            self.terminal.writeln_ansi(&conflict_string, &RedANSI);
+        }
+
+        if conflicts.is_empty() {
+            self.terminal
+                .writeln_ansi("No broken requirements found.", &GreenANSI);
+        } else {
+            let conflicts_length: String = conflicts.len().to_string();
+            let parts: [&str; 2] = ["Broken Requirements: ", &conflicts_length];
+            self.terminal.writeln_parameter(&parts, &RedANSI);
+        }
+        conflicts.is_empty()
+    }
+
     fn list_packages(&self, packages: &Vec<PipPackage>) {
         for package in packages {
-            let package_string: String = package.get_string();
-            println!("{}", package_string);
+            self.print_package(package);
         }
 
         let packages_length: String = packages.len().to_string();
@@ -292,20 +631,166 @@ impl PythonPackagesCommand {
         self.terminal.writeln_parameter(&parts, &YellowANSI);
     }
 
-    fn save_packages(&self, env_dir: &WPath, packages: &Vec<PipPackage>) {
-        let filename: &str = self.get_filename_from_option();
-        let file_path: WPath = env_dir.join(filename);
-        let mut file: File = File::create(&file_path).expect("Could not create file");
+    fn print_package(&self, package: &PipPackage) {
+        let dist_info: &PipDistInfo = package.get_dist_info();
+        let mut package_string: String = package.get_string();
 
-        for package in packages {
-            let requirement_string: String = package.get_requirement_string();
-            writeln!(file, "{}", requirement_string).expect("Could not write to file");
+        if let Some(installer) = dist_info.get_installer() {
+            package_string.push_str(&format!(" | Installer: {}", installer));
+        }
+        if !dist_info.get_record().is_empty() {
+            let size: String = format_size(dist_info.get_size() as usize);
+            let files: usize = dist_info.get_files().len();
+            package_string.push_str(&format!(" | Size: {} | Files: {}", size, files));
         }
+        println!("{}", package_string);
 
-        let terminal = Terminal::new();
-        let path_str: String = format!("[{:?}]", file_path);
-        let parts: [&str; 2] = ["Packages List Saved: ", &path_str];
-        terminal.writeln_parameter(&parts, &GreenANSI);
+        if let Some(direct_url) = dist_info.get_direct_url() {
+            let label: &str = if direct_url.is_editable() {
+                "  Editable: "
+            } else {
+                "  URL: "
+            };
+            let url: String = direct_url.get_requirement_url();
+            let parts: [&str; 2] = [label, &url];
+            self.terminal.writeln_parameter(&parts, &CyanANSI);
+        }
+
+        let scripts: Vec<&str> = dist_info.get_scripts();
+        if !scripts.is_empty() {
+            let scripts: String = scripts.join(", ");
+            let parts: [&str; 2] = ["  Scripts: ", &scripts];
+            self.terminal.writeln_parameter(&parts, &CyanANSI);
+        }
+    }
+
+    fn save_packages(&self, env_dir: &WPath, packages: &[PipPackage]) {
+        let format: PipExportFormat = self.get_export_format();
+        let file_path: WPath = self.get_output_path(env_dir);
+        let exporter: PipExporter = PipExporter::new(format, env_dir);
+
+        match exporter.export(packages, &file_path) {
+            Ok(()) => {
+                let path_str: String = format!("[{:?}]", file_path);
+                let parts: [&str; 2] = ["Packages List Saved: ", &path_str];
+                self.terminal.writeln_parameter(&parts, &GreenANSI);
+            }
+            Err(error) => {
+                let file_path: String = file_path.get_path_buf().display().to_string();
+                let error_string: String =
+                    format!("Error: Could not save {}: {}", file_path, error);
+                self.terminal.writeln_ansi(&error_string, &RedANSI);
+            }
+        }
+    }
+}
+
+pub struct PythonDiffEnvsCommand {
+    option: DiffEnvsOption,
+    terminal: Terminal,
+}
+
+impl PythonDiffEnvsCommand {
+    pub fn new(option: DiffEnvsOption) -> Self {
+        let terminal: Terminal = Terminal::new();
+        PythonDiffEnvsCommand { option, terminal }
+    }
+
+    pub fn execute_command(&self) {
+        self.print_search_parameters();
+
+        let mut comparison: PipComparison = PipComparison::new();
+        comparison.set_show_all(self.option.unchanged);
+
+        if let Some(against) = &self.option.against {
+            let requirements_path: WPath = WPath::from_string(against);
+            let requirements_parser: PipRequirementsParser = PipRequirementsParser::new();
+            match requirements_parser.parse(&requirements_path) {
+                Ok(requirements) => comparison.set_requirements(&requirements),
+                Err(error) => {
+                    let error_string: String =
+                        format!("Error: Could not read {:?}: {}", requirements_path, error);
+                    self.terminal.writeln_ansi(&error_string, &RedANSI);
+                    return;
+                }
+            }
+        }
+
+        let venv_selector: VirtualEnvSelector = VirtualEnvSelector::new(&self.option.selection);
+        let venv_cfgs: Vec<VirtualEnvCFG> = venv_selector.find_configs();
+        let mut labels: Vec<(String, WPath)> = Vec::new();
+
+        for venv_cfg in venv_cfgs.iter() {
+            let env_dir: WPath = venv_cfg.get_environment_directory();
+            let packages_dir: WPath = venv_cfg.get_site_packages_directory();
+            let package_parser: PipPackageParser = PipPackageParser::new(&packages_dir);
+
+            match package_parser.get_packages() {
+                Ok(packages) => {
+                    let label: String = format!("Env {}", labels.len() + 1);
+                    comparison.add_environment(&label, &packages);
+                    labels.push((label, env_dir));
+                }
+                Err(error) => {
+                    let error_string: String =
+                        format!("Error: Could not read packages of {:?}: {}", env_dir, error);
+                    self.terminal.writeln_ansi(&error_string, &RedANSI);
+                }
+            }
+        }
+
+        let required_length: usize = if self.option.against.is_some() { 1 } else { 2 };
+        if labels.len() < required_length {
+            let string: String = format!(
+                "At least {} environment(s) are required for a comparison.",
+                required_length
+            );
+            self.terminal.writeln_ansi(&string, &YellowANSI);
+            return;
+        }
+
+        println!();
+        for (label, env_dir) in labels.iter() {
+            let label: String = format!("{}: ", label);
+            let env_dir: String = format!("{:?}", env_dir);
+            let parts: [&str; 2] = [&label, &env_dir];
+            self.terminal.writeln_parameter(&parts, &YellowANSI);
+        }
+        println!();
+
+        self.print_comparison(&comparison);
+    }
+}
+
+impl PythonDiffEnvsCommand {
+    fn print_search_parameters(&self) {
+        let venv_selector: VirtualEnvSelector = VirtualEnvSelector::new(&self.option.selection);
+
+        let mut table: DynamicTable = DynamicTable::new(0.6, 1);
+        table.set_header("Diff Environments Parameters");
+        venv_selector.add_parameters(&mut table);
+        if let Some(against) = &self.option.against {
+            table.add_fmt_parameter("Against", against);
+        }
+        table.add_fmt_parameter("Unchanged", self.option.unchanged);
+        table.print();
+        println!();
+    }
+
+    fn print_comparison(&self, comparison: &PipComparison) {
+        let table: GridTable = comparison.get_table();
+        if table.get_row_count() == 0 {
+            self.terminal
+                .writeln_ansi("No differences found.", &GreenANSI);
+            return;
+        }
+        table.print();
+
+        self.terminal.write_ansi("Missing", &RedANSI);
+        self.terminal.write(" | ");
+        self.terminal.write_ansi("Newer", &GreenANSI);
+        self.terminal.write(" | ");
+        self.terminal.writeln_ansi("Older", &YellowANSI);
     }
 }
 
@@ -319,10 +804,10 @@ impl PythonDLCommand {
     }
 
     pub async fn execute_command(&mut self) {
-        let ftp_retriever: PythonFTPRetriever = PythonFTPRetriever::new();
+        let ftp_retriever: PythonFTPRetriever = self.get_ftp_retriever();
         let mut version: SemanticVersion = self.option.version.clone();
 
-        self.print_search_parameters();
+        self.print_search_parameters(&ftp_retriever);
         let url: Option<String> = self.get_url(&ftp_retriever, &mut version).await;
 
         if self.option.list_structure {
@@ -330,32 +815,244 @@ impl PythonDLCommand {
             return;
         }
 
-        self.download_from_url(&url).await;
+        self.download_from_url(&ftp_retriever, &url).await;
+    }
+
+    /// Resolves the URL of `version`'s file. With `--recent-patch`, `version` is updated
+    /// to the patch that was found.
+    pub async fn resolve(&self, version: &mut SemanticVersion) -> Option<String> {
+        let ftp_retriever: PythonFTPRetriever = self.get_ftp_retriever();
+        self.get_url(&ftp_retriever, version).await
+    }
+
+    /// Downloads a URL from `resolve`, returning where the file was saved.
+    pub async fn download(&self, url: &str) -> Option<PathBuf> {
+        let ftp_retriever: PythonFTPRetriever = self.get_ftp_retriever();
+        let url: Option<String> = Some(url.to_string());
+        self.download_from_url(&ftp_retriever, &url).await
     }
 }
 
 impl PythonDLCommand {
-    async fn download_from_url(&self, url: &Option<String>) {
+    async fn download_from_url(
+        &self,
+        ftp_retriever: &PythonFTPRetriever,
+        url: &Option<String>,
+    ) -> Option<PathBuf> {
         let terminal: Terminal = Terminal::new();
 
         if let Some(url) = url {
             let parts: [&str; 2] = ["Found version: ", url];
             terminal.writeln_parameter(&parts, &GreenANSI);
 
-            let https: HTTPS = HTTPS::new();
-            let result: Result<String, Box<dyn Error>> = https.download_file(&url).await;
-            if let Ok(file_name) = result {
-                let parts: [&str; 2] = ["File Downloaded: ", &file_name];
+            let destination: PathBuf = match self.get_destination(url) {
+                Some(destination) => destination,
+                None => {
+                    terminal.writeln_ansi("Unable to get file name from URL.", &RedANSI);
+                    process::exit(1);
+                }
+            };
+
+            let sidecar_urls: Vec<String> = ftp_retriever.get_sidecar_urls(url).await;
+            let verifier: PythonArtifactVerifier = PythonArtifactVerifier::new(&sidecar_urls);
+            for signature_url in verifier.get_signature_urls() {
+                let parts: [&str; 2] = ["Signature: ", signature_url];
+                terminal.writeln_parameter(&parts, &CyanANSI);
+            }
+            let expected: Result<Option<String>, String> = verifier
+                .get_expected_digest()
+                .await
+                .map_err(|error| error.to_string());
+
+            if destination.exists() && !self.replace_existing(&verifier, &expected, &destination) {
+                return Some(destination);
+            }
+
+            let cache: Option<DownloadCache> = if self.option.no_cache {
+                None
+            } else {
+                DownloadCache::new()
+            };
+            let cached_path: Option<PathBuf> = cache.as_ref().and_then(|cache| cache.get_path(url));
+            let download_path: PathBuf = cached_path.clone().unwrap_or(destination.clone());
+
+            let is_cached: bool = cached_path.is_some() && download_path.exists() && {
+                let verification: Result<PythonVerification, String> =
+                    self.get_verification(&verifier, &expected, &download_path);
+                !matches!(verification, Ok(PythonVerification::Mismatch { .. }))
+            };
+
+            if is_cached {
+                let path_string: String = download_path.display().to_string();
+                let parts: [&str; 2] = ["Cached: ", &path_string];
                 terminal.writeln_parameter(&parts, &GreenANSI);
-                return;
             } else {
-                let error: String = result.unwrap_err().to_string();
-                let parts: [&str; 2] = ["Error: ", &error];
-                terminal.writeln_parameter(&parts, &RedANSI);
+                let https: HTTPS = HTTPS::new();
+                let result: Result<(), Box<dyn Error>> =
+                    https.download_to(url, &download_path).await;
+                if let Err(error) = result {
+                    self.print_download_error(error.as_ref());
+                    process::exit(1);
+                }
+            }
+
+            let verification: Result<PythonVerification, String> =
+                self.get_verification(&verifier, &expected, &download_path);
+            self.verify_file(&download_path, verification);
+
+            if let (Some(cache), Some(cached_path)) = (&cache, &cached_path) {
+                if let Err(error) = cache.copy_to(cached_path, &destination) {
+                    let error: String = error.to_string();
+                    let parts: [&str; 2] = ["Error: ", &error];
+                    terminal.writeln_parameter(&parts, &RedANSI);
+                    process::exit(1);
+                }
             }
+
+            let destination_string: String = destination.display().to_string();
+            let parts: [&str; 2] = ["File Downloaded: ", &destination_string];
+            terminal.writeln_parameter(&parts, &GreenANSI);
+            return Some(destination);
         }
         let string: &str = "Python version not found.";
         terminal.writeln_ansi(&string, &RedANSI);
+        None
+    }
+
+    /// Resolves `--output` or `--output-dir` and the file name from the URL, defaulting
+    /// to the current directory.
+    fn get_destination(&self, url: &str) -> Option<PathBuf> {
+        if let Some(output) = &self.option.output {
+            return Some(PathBuf::from(output));
+        }
+
+        let file_name: &str = url.rsplit('/').next().filter(|name| !name.is_empty())?;
+        let directory: PathBuf = match &self.option.output_dir {
+            Some(output_dir) => PathBuf::from(output_dir),
+            None => PathBuf::new(),
+        };
+        Some(directory.join(file_name))
+    }
+
+    /// Applies `--if-exists` to an existing destination, returning whether to download.
+    fn replace_existing(
+        &self,
+        verifier: &PythonArtifactVerifier,
+        expected: &Result<Option<String>, String>,
+        destination: &Path,
+    ) -> bool {
+        let terminal: Terminal = Terminal::new();
+        let destination_string: String = destination.display().to_string();
+
+        match self.option.if_exists.as_str() {
+            "overwrite" => true,
+            "skip" => {
+                let verification: Result<PythonVerification, String> =
+                    self.get_verification(verifier, expected, destination);
+                if let Ok(PythonVerification::Verified(_)) = verification {
+                    let parts: [&str; 2] = ["Skipped, already verified: ", &destination_string];
+                    terminal.writeln_parameter(&parts, &GreenANSI);
+                    return false;
+                }
+                let parts: [&str; 2] = ["Unverified, downloading again: ", &destination_string];
+                terminal.writeln_parameter(&parts, &YellowANSI);
+                true
+            }
+            _ => {
+                let parts: [&str; 2] = ["File already exists: ", &destination_string];
+                terminal.writeln_parameter(&parts, &RedANSI);
+                let string: &str = "Use --if-exists overwrite or --if-exists skip.";
+                terminal.writeln_ansi(string, &YellowANSI);
+                process::exit(1);
+            }
+        }
+    }
+
+    fn get_verification(
+        &self,
+        verifier: &PythonArtifactVerifier,
+        expected: &Result<Option<String>, String>,
+        file_path: &Path,
+    ) -> Result<PythonVerification, String> {
+        let expected: Option<&str> = match expected {
+            Ok(expected) => expected.as_deref(),
+            Err(error) => return Err(format!("Unable to fetch digest: {}", error)),
+        };
+        let file_path: WPath = WPath::from_path_buf(&file_path.to_path_buf());
+        verifier
+            .verify_against(&file_path, expected)
+            .map_err(|error| format!("Unable to verify digest: {}", error))
+    }
+
+    fn print_download_error(&self, error: &(dyn Error + 'static)) {
+        let terminal: Terminal = Terminal::new();
+        let parts: [&str; 2] = ["Download failed: ", &error.to_string()];
+        terminal.writeln_parameter(&parts, &RedANSI);
+
+        match error.downcast_ref::<HTTPSError>() {
+            Some(HTTPSError::Status { status, .. }) if status.as_u16() == 404 => {
+                terminal.writeln_ansi("The file no longer exists on the server.", &YellowANSI);
+            }
+            Some(HTTPSError::ContentType { .. }) => {
+                let string: &str = "The server returned an error page instead of the file.";
+                terminal.writeln_ansi(string, &YellowANSI);
+            }
+            Some(HTTPSError::Status { .. }) => {}
+            None => {
+                let string: &str =
+                    "Any partial download was kept, run the command again to resume.";
+                terminal.writeln_ansi(string, &YellowANSI);
+            }
+        }
+    }
+
+    /// Reports the result of checking a download against its published digest. With
+    /// `--verify`, a missing digest or a mismatch removes the file and exits with an error.
+    fn verify_file(&self, file_path: &Path, verification: Result<PythonVerification, String>) {
+        let terminal: Terminal = Terminal::new();
+
+        let error: Option<String> = match verification {
+            Ok(PythonVerification::Verified(digest)) => {
+                let parts: [&str; 2] = ["SHA-256 Verified: ", &digest];
+                terminal.writeln_parameter(&parts, &GreenANSI);
+                None
+            }
+            Ok(PythonVerification::Mismatch { expected, actual }) => Some(format!(
+                "SHA-256 mismatch, expected {} but got {}",
+                expected, actual
+            )),
+            Ok(PythonVerification::Unavailable) => {
+                Some("No published SHA-256 digest was found".to_string())
+            }
+            Err(error) => Some(error),
+        };
+
+        if let Some(error) = error {
+            if !self.option.verify {
+                let parts: [&str; 2] = ["Warning: ", &error];
+                terminal.writeln_parameter(&parts, &YellowANSI);
+                return;
+            }
+
+            let parts: [&str; 2] = ["Error: ", &error];
+            terminal.writeln_parameter(&parts, &RedANSI);
+            if fs::remove_file(file_path).is_ok() {
+                let path_string: String = file_path.display().to_string();
+                let parts: [&str; 2] = ["Removed: ", &path_string];
+                terminal.writeln_parameter(&parts, &RedANSI);
+            }
+            process::exit(1);
+        }
+    }
+
+    fn get_ftp_retriever(&self) -> PythonFTPRetriever {
+        let mirror: Option<&str> = self.option.mirror.as_deref();
+        let mut ftp_retriever: PythonFTPRetriever = PythonFTPRetriever::from_mirror(mirror);
+        ftp_retriever.set_include_pre_release(self.option.pre);
+        if let Some(extension) = &self.option.extension {
+            ftp_retriever.set_extension(extension);
+        }
+        ftp_retriever
     }
 
     async fn get_url(
@@ -380,21 +1077,450 @@ impl PythonDLCommand {
         url
     }
 
-    fn print_search_parameters(&self) {
+    fn print_search_parameters(&self, ftp_retriever: &PythonFTPRetriever) {
         let arch: &str = &self.option.architecture;
         let platform: &str = &self.option.platform;
         let package_type: &str = &self.option.package_type;
         let recent_patch: bool = self.option.recent_patch;
         let list_structure: bool = self.option.list_structure;
+        let verify: bool = self.option.verify;
+        let if_exists: &str = &self.option.if_exists;
+        let no_cache: bool = self.option.no_cache;
 
         let mut table: DynamicTable = DynamicTable::new(0.6, 1);
         table.set_header("Download Parameters");
+        table.add_fmt_parameter("Mirror", ftp_retriever.get_url());
         table.add_fmt_parameter("Arch", arch);
         table.add_fmt_parameter("Platform", platform);
         table.add_fmt_parameter("Type", package_type);
+        if let Some(extension) = &self.option.extension {
+            table.add_fmt_parameter("Extension", extension);
+        }
         table.add_fmt_parameter("Most recent Patch", recent_patch);
+        table.add_fmt_parameter("Pre-releases", self.option.pre);
         table.add_fmt_parameter("List Results", list_structure);
+        table.add_fmt_parameter("Verify", verify);
+        if let Some(output) = &self.option.output {
+            table.add_fmt_parameter("Output", output);
+        }
+        if let Some(output_dir) = &self.option.output_dir {
+            table.add_fmt_parameter("Output Directory", output_dir);
+        }
+        table.add_fmt_parameter("If Exists", if_exists);
+        table.add_fmt_parameter("No Cache", no_cache);
+        table.print();
+        println!();
+    }
+}
+
+pub struct PythonListRemoteCommand {
+    option: ListRemoteOption,
+    terminal: Terminal,
+}
+
+impl PythonListRemoteCommand {
+    pub fn new(option: ListRemoteOption) -> Self {
+        let terminal: Terminal = Terminal::new();
+        PythonListRemoteCommand { option, terminal }
+    }
+
+    pub async fn execute_command(&self) {
+        let mirror: Option<&str> = self.option.mirror.as_deref();
+        let mut ftp_retriever: PythonFTPRetriever = PythonFTPRetriever::from_mirror(mirror);
+        ftp_retriever.set_include_pre_release(self.option.pre);
+
+        self.print_search_parameters(&ftp_retriever);
+
+        let directories: Vec<(SemanticVersion, String)> =
+            ftp_retriever.get_release_directories().await;
+        if directories.is_empty() {
+            let parts: [&str; 2] = ["Unable to list releases: ", ftp_retriever.get_url()];
+            self.terminal.writeln_parameter(&parts, &RedANSI);
+            process::exit(1);
+        }
+
+        let mut releases: Vec<(SemanticVersion, Vec<String>)> = Vec::new();
+        let mut scanned: bool = false;
+        for (version, directory) in directories {
+            if releases.len() >= self.option.limit {
+                break;
+            }
+            if !self.match_version(&version) {
+                continue;
+            }
+
+            let parts: [&str; 2] = ["\rScanning: ", &version.get_string()];
+            self.terminal.write_parameter(&parts, &YellowANSI);
+            scanned = true;
+
+            let release_files: Option<Vec<PythonFilename>> =
+                ftp_retriever.get_release_files(&directory).await;
+            if let Some(release_files) = release_files {
+                releases.extend(self.get_releases(&release_files));
+            }
+        }
+        if scanned {
+            println!();
+        }
+        releases.truncate(self.option.limit);
+
+        if releases.is_empty() {
+            self.terminal
+                .writeln_ansi("No Python releases found.", &YellowANSI);
+            return;
+        }
+
+        let mut table: DynamicTable = DynamicTable::new(0.8, 1);
+        table.set_header("Python Releases");
+        for (version, artifacts) in releases {
+            table.add_string_parameter(version.get_string(), artifacts.join(", "));
+        }
+        table.print();
+    }
+}
+
+impl PythonListRemoteCommand {
+    fn print_search_parameters(&self, ftp_retriever: &PythonFTPRetriever) {
+        let mut table: DynamicTable = DynamicTable::new(0.6, 1);
+        table.set_header("List Remote Parameters");
+        table.add_fmt_parameter("Mirror", ftp_retriever.get_url());
+        if let Some(version) = &self.option.version {
+            table.add_fmt_parameter("Version", version);
+        }
+        if let Some(architecture) = &self.option.architecture {
+            table.add_fmt_parameter("Arch", architecture);
+        }
+        if let Some(platform) = &self.option.platform {
+            table.add_fmt_parameter("Platform", platform);
+        }
+        if let Some(package_type) = &self.option.package_type {
+            table.add_fmt_parameter("Type", package_type);
+        }
+        table.add_fmt_parameter("Pre-releases", self.option.pre);
+        table.add_fmt_parameter("Limit", self.option.limit);
+        table.print();
+        println!();
+    }
+
+    /// Matches the major or major.minor given with `--version`.
+    fn match_version(&self, version: &SemanticVersion) -> bool {
+        let filter: &str = match &self.option.version {
+            Some(filter) => filter,
+            None => return true,
+        };
+        let parts: Vec<usize> = filter
+            .split('.')
+            .filter_map(|part| part.parse::<usize>().ok())
+            .collect();
+        let (major, minor): (usize, usize) = version.get_2p_version();
+
+        match parts.as_slice() {
+            [filter_major] => *filter_major == major,
+            [filter_major, filter_minor] => *filter_major == major && *filter_minor == minor,
+            _ => false,
+        }
+    }
+
+    fn match_filters(&self, filename: &PythonFilename) -> bool {
+        let filters: [(&Option<String>, &str); 3] = [
+            (&self.option.architecture, filename.get_architecture()),
+            (&self.option.platform, filename.get_platform()),
+            (&self.option.package_type, filename.get_package_type()),
+        ];
+        filters.iter().all(|(filter, value)| match filter {
+            Some(filter) => filter == value,
+            None => true,
+        })
+    }
+
+    /// Groups the artifacts of a version directory by release, since a directory also
+    /// holds the pre-releases leading up to it. The final release is listed first.
+    fn get_releases(
+        &self,
+        release_files: &[PythonFilename],
+    ) -> Vec<(SemanticVersion, Vec<String>)> {
+        let mut releases: Vec<(SemanticVersion, Vec<String>)> = Vec::new();
+
+        for filename in release_files
+            .iter()
+            .filter(|filename| self.match_filters(filename))
+        {
+            let version: &SemanticVersion = filename.get_version();
+            let artifact: String = self.get_artifact_label(filename);
+            let position: Option<usize> = releases
+                .iter()
+                .position(|(release, _)| release.get_string() == version.get_string());
+
+            match position {
+                Some(position) => releases[position].1.push(artifact),
+                None => releases.push((version.clone(), vec![artifact])),
+            }
+        }
+
+        for (_, artifacts) in releases.iter_mut() {
+            artifacts.sort();
+            artifacts.dedup();
+        }
+        releases.sort_by(
+            |(a, _), (b, _)| match (a.get_pre_release(), b.get_pre_release()) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(a), Some(b)) => b.partial_cmp(a).unwrap_or(Ordering::Equal),
+            },
+        );
+        releases
+    }
+
+    /// Describes an artifact as e.g. `windows/amd64 standard (exe)`.
+    fn get_artifact_label(&self, filename: &PythonFilename) -> String {
+        let platform: &str = filename.get_platform();
+        let architecture: &str = filename.get_architecture();
+        let package_type: &str = filename.get_package_type();
+        let extension: &str = filename.get_extension();
+
+        if architecture == "n/a" {
+            return format!("{} {} ({})", platform, package_type, extension);
+        }
+        format!(
+            "{}/{} {} ({})",
+            platform, architecture, package_type, extension
+        )
+    }
+}
+
+pub struct PythonInstallCommand {
+    option: PythonInstallOption,
+    terminal: Terminal,
+}
+
+impl PythonInstallCommand {
+    pub fn new(option: PythonInstallOption) -> Self {
+        let terminal: Terminal = Terminal::new();
+        PythonInstallCommand { option, terminal }
+    }
+
+    pub async fn execute_command(&self) {
+        if cfg!(windows) {
+            let string: &str =
+                "Building from source is not supported on Windows, use python download instead.";
+            self.terminal.writeln_ansi(string, &RedANSI);
+            process::exit(1);
+        }
+
+        let (base_path, build_path): (WPath, PathBuf) =
+            match (dirs::data_local_dir(), dirs::cache_dir()) {
+                (Some(data_dir), Some(cache_dir)) => {
+                    let base_path: WPath = WPath::from_path_buf(&data_dir.join("Programs/Python"));
+                    (base_path, cache_dir.join("arranger").join("build"))
+                }
+                _ => {
+                    let string: &str = "Unable to find the local data and cache directories.";
+                    self.terminal.writeln_ansi(string, &RedANSI);
+                    process::exit(1);
+                }
+            };
+        self.print_install_parameters(&base_path);
+
+        let mut version: SemanticVersion = self.option.version.clone();
+        let download_option: PythonDownloadOption = self.get_download_option(&build_path);
+        let download_command: PythonDLCommand = PythonDLCommand::new(download_option);
+        let url: String = match download_command.resolve(&mut version).await {
+            Some(url) => url,
+            None => {
+                self.terminal
+                    .writeln_ansi("Python version not found.", &RedANSI);
+                process::exit(1);
+            }
+        };
+
+        // Check before downloading so an existing install does not fetch the archive.
+        let install_path: WPath = PythonEnvironment::get_install_path(&base_path, &version);
+        if !self.option.force && self.is_installed(&install_path, &version) {
+            let install_string: String = format!("{:?}", install_path);
+            let parts: [&str; 2] = ["Already installed: ", &install_string];
+            self.terminal.writeln_parameter(&parts, &GreenANSI);
+            self.terminal
+                .writeln_ansi("Use --force to reinstall.", &YellowANSI);
+            return;
+        }
+
+        let archive_path: PathBuf = match download_command.download(&url).await {
+            Some(archive_path) => archive_path,
+            None => process::exit(1),
+        };
+        println!();
+
+        let source_path: WPath = match self.extract_archive(&archive_path, &build_path) {
+            Some(source_path) => source_path,
+            None => process::exit(1),
+        };
+        if !self.build(&source_path, &install_path) {
+            process::exit(1);
+        }
+        let _ = fs::remove_dir_all(&source_path);
+        let _ = fs::remove_file(&archive_path);
+
+        // Installs are shared by every patch of a major.minor version, like `python venv`.
+        let mut environment_version: SemanticVersion = version.clone();
+        environment_version.set_patch(0);
+        let environment: Option<PythonEnvironment> =
+            PythonEnvironment::new(&base_path, &environment_version);
+
+        match environment {
+            Some(environment) => {
+                let executable: String = format!("{:?}", environment.get_python_executable());
+                let parts: [&str; 2] = ["Installed: ", &executable];
+                self.terminal.writeln_parameter(&parts, &GreenANSI);
+            }
+            None => process::exit(1),
+        }
+    }
+}
+
+impl PythonInstallCommand {
+    fn print_install_parameters(&self, base_path: &WPath) {
+        let mut table: DynamicTable = DynamicTable::new(0.6, 1);
+        table.set_header("Install Parameters");
+        table.add_fmt_parameter("Version", self.option.version.get_string());
+        table.add_fmt_parameter("Most recent Patch", self.option.recent_patch);
+        table.add_fmt_parameter("Pre-releases", self.option.pre);
+        table.add_fmt_parameter("Verify", !self.option.unverified);
+        table.add_fmt_parameter("Optimize", self.option.optimize);
+        table.add_fmt_parameter("Jobs", self.get_jobs());
+        table.add_fmt_parameter("Force", self.option.force);
+        table.add_fmt_parameter("No Cache", self.option.no_cache);
+        if let Some(mirror) = &self.option.mirror {
+            table.add_fmt_parameter("Mirror", mirror);
+        }
+        let base_string: String = base_path.get_path_buf().display().to_string();
+        table.add_fmt_parameter("Install Directory", base_string);
         table.print();
         println!();
     }
+
+    /// Downloads the source archive into the build directory, preferring `tar.xz`.
+    fn get_download_option(&self, build_path: &Path) -> PythonDownloadOption {
+        PythonDownloadOption {
+            version: self.option.version.clone(),
+            recent_patch: self.option.recent_patch,
+            pre: self.option.pre,
+            list_structure: false,
+            architecture: "n/a".to_string(),
+            platform: "any".to_string(),
+            package_type: "source".to_string(),
+            extension: None,
+            verify: !self.option.unverified,
+            output_dir: Some(build_path.display().to_string()),
+            output: None,
+            if_exists: "overwrite".to_string(),
+            no_cache: self.option.no_cache,
+            mirror: self.option.mirror.clone(),
+        }
+    }
+
+    fn get_jobs(&self) -> usize {
+        self.option.jobs.unwrap_or_else(|| {
+            thread::available_parallelism()
+                .map(|jobs| jobs.get())
+                .unwrap_or(1)
+        })
+    }
+
+    fn is_installed(&self, install_path: &WPath, version: &SemanticVersion) -> bool {
+        let executable_name: String = format!("python{}", version.get_2p_string());
+        let python_executable: WPath = install_path.join("bin").join(executable_name);
+        if !python_executable.exists() {
+            return false;
+        }
+
+        let installed: Option<SemanticVersion> =
+            PythonEnvironment::get_executable_version(&python_executable);
+        installed.is_some_and(|installed| installed.get_string() == version.get_string())
+    }
+
+    /// Extracts the archive into the build directory, replacing sources left by an
+    /// earlier build, and returns the source directory.
+    fn extract_archive(&self, archive_path: &Path, build_path: &Path) -> Option<WPath> {
+        let file_name: String = archive_path.file_name()?.to_string_lossy().to_string();
+        let directory_name: &str = [".tar.xz", ".tar.bz2", ".tar.gz", ".tgz"]
+            .iter()
+            .find_map(|extension| file_name.strip_suffix(extension))?;
+        let source_path: PathBuf = build_path.join(directory_name);
+        if source_path.exists() {
+            let _ = fs::remove_dir_all(&source_path);
+        }
+
+        let source_string: String = source_path.display().to_string();
+        let parts: [&str; 2] = ["Extracting: ", &source_string];
+        self.terminal.writeln_parameter(&parts, &CyanANSI);
+
+        let archive_string: String = archive_path.display().to_string();
+        let build_string: String = build_path.display().to_string();
+        let args: [&str; 4] = ["-xf", &archive_string, "-C", &build_string];
+        let command: CommandExecute = CommandExecute::new();
+        let response: Option<CommandResponse> =
+            command.execute_command(&WPath::from_string("tar"), &args);
+
+        match response {
+            Some(response) if response.get_status().success() && source_path.is_dir() => {
+                Some(WPath::from_path_buf(&source_path))
+            }
+            Some(response) => {
+                response.print();
+                self.terminal
+                    .writeln_ansi("Unable to extract the source archive.", &RedANSI);
+                None
+            }
+            None => {
+                self.terminal
+                    .writeln_ansi("Unable to run tar to extract the source archive.", &RedANSI);
+                None
+            }
+        }
+    }
+
+    /// Runs `configure`, `make` and `make altinstall`, which leaves any `python3` of the
+    /// system untouched. Sources are kept when a step fails.
+    fn build(&self, source_path: &WPath, install_path: &WPath) -> bool {
+        let install_string: String = install_path.get_path_buf().display().to_string();
+        let prefix_arg: String = format!("--prefix={}", install_string);
+        let jobs_arg: String = format!("-j{}", self.get_jobs());
+
+        let mut configure_args: Vec<&str> = vec![&prefix_arg, "--with-ensurepip=install"];
+        if self.option.optimize {
+            configure_args.push("--enable-optimizations");
+        }
+        let configure: WPath = source_path.join("configure");
+        let make: WPath = WPath::from_string("make");
+        let steps: [(&WPath, Vec<&str>); 3] = [
+            (&configure, configure_args),
+            (&make, vec![&jobs_arg]),
+            (&make, vec!["altinstall"]),
+        ];
+
+        let command: CommandExecute = CommandExecute::new();
+        for (program, args) in steps {
+            let program_name: String = program
+                .get_path_buf()
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let step: String = format!("{} {}", program_name, args.join(" "));
+            let parts: [&str; 2] = ["Running: ", &step];
+            self.terminal.writeln_parameter(&parts, &CyanANSI);
+
+            let status: Option<ExitStatus> =
+                command.execute_spawn_command_in(program, &args, source_path);
+            if !status.is_some_and(|status| status.success()) {
+                let parts: [&str; 2] = ["Build step failed: ", &step];
+                self.terminal.writeln_parameter(&parts, &RedANSI);
+                let source_string: String = format!("{:?}", source_path);
+                let parts: [&str; 2] = ["Sources kept in: ", &source_string];
+                self.terminal.writeln_parameter(&parts, &YellowANSI);
+                return false;
+            }
+        }
+        true
+    }
 }
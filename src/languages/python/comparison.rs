@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::pep440::PipVersion;
+use super::pep508::PipRequirement;
+use super::pip::{PipPackage, PipPackageName};
+
+use crate::misc::table_display::{GridCell, GridTable};
+use crate::terminal::{GreenANSI, RedANSI, YellowANSI};
+
+pub struct PipComparison {
+    labels: Vec<String>,
+    environments: Vec<HashMap<PipPackageName, PipVersion>>,
+    requirements: Option<BTreeMap<PipPackageName, PipRequirement>>,
+    show_all: bool,
+}
+
+impl PipComparison {
+    pub fn new() -> Self {
+        PipComparison {
+            labels: Vec::new(),
+            environments: Vec::new(),
+            requirements: None,
+            show_all: false,
+        }
+    }
+
+    pub fn add_environment(&mut self, label: &str, packages: &[PipPackage]) {
+        let packages: HashMap<PipPackageName, PipVersion> = packages
+            .iter()
+            .map(|package| (package.get_name().clone(), package.get_version().clone()))
+            .collect();
+        self.labels.push(label.to_string());
+        self.environments.push(packages);
+    }
+
+    /// Compares every environment against a requirements file instead of each other.
+    pub fn set_requirements(&mut self, requirements: &[PipRequirement]) {
+        let requirements: BTreeMap<PipPackageName, PipRequirement> = requirements
+            .iter()
+            .map(|requirement| (requirement.get_name().clone(), requirement.clone()))
+            .collect();
+        self.requirements = Some(requirements);
+    }
+
+    /// Includes packages that are identical everywhere in the table.
+    pub fn set_show_all(&mut self, show_all: bool) {
+        self.show_all = show_all;
+    }
+
+    pub fn get_table(&self) -> GridTable {
+        let mut columns: Vec<String> = vec!["Package".to_string()];
+        if self.requirements.is_some() {
+            columns.push("Required".to_string());
+        }
+        columns.extend(self.labels.iter().cloned());
+
+        let mut table: GridTable = GridTable::new(0);
+        table.set_header("Package Comparison");
+        table.set_columns(&columns);
+
+        for name in self.get_package_names() {
+            let row: Option<Vec<GridCell>> = match &self.requirements {
+                Some(requirements) => self.get_requirement_row(&name, requirements.get(&name)),
+                None => self.get_environment_row(&name),
+            };
+            if let Some(row) = row {
+                table.add_row(row);
+            }
+        }
+        table
+    }
+}
+
+impl PipComparison {
+    fn get_package_names(&self) -> BTreeSet<PipPackageName> {
+        let mut names: BTreeSet<PipPackageName> = BTreeSet::new();
+        for environment in self.environments.iter() {
+            names.extend(environment.keys().cloned());
+        }
+        if let Some(requirements) = &self.requirements {
+            names.extend(requirements.keys().cloned());
+        }
+        names
+    }
+
+    fn get_versions(&self, name: &PipPackageName) -> Vec<Option<&PipVersion>> {
+        self.environments
+            .iter()
+            .map(|environment| environment.get(name))
+            .collect()
+    }
+
+    fn get_environment_row(&self, name: &PipPackageName) -> Option<Vec<GridCell>> {
+        let versions: Vec<Option<&PipVersion>> = self.get_versions(name);
+        let newest: Option<&PipVersion> = versions.iter().flatten().max().copied();
+        let is_identical: bool = versions.iter().all(|version| *version == newest);
+
+        if is_identical && !self.show_all {
+            return None;
+        }
+
+        let mut row: Vec<GridCell> = vec![GridCell::new(name.get_raw_string())];
+        for version in versions {
+            let cell: GridCell = match version {
+                None => GridCell::new_colored("-", RedANSI),
+                Some(version) if is_identical => GridCell::new(&version.get_string()),
+                Some(version) if Some(version) == newest => {
+                    GridCell::new_colored(&version.get_string(), GreenANSI)
+                }
+                Some(version) => GridCell::new_colored(&version.get_string(), YellowANSI),
+            };
+            row.push(cell);
+        }
+        Some(row)
+    }
+
+    fn get_requirement_row(
+        &self,
+        name: &PipPackageName,
+        requirement: Option<&PipRequirement>,
+    ) -> Option<Vec<GridCell>> {
+        let versions: Vec<Option<&PipVersion>> = self.get_versions(name);
+        let is_satisfied: bool = versions.iter().all(|version| match (requirement, version) {
+            (Some(requirement), Some(version)) => self.is_satisfied(requirement, version),
+            _ => false,
+        });
+
+        if is_satisfied && !self.show_all {
+            return None;
+        }
+
+        let mut row: Vec<GridCell> = vec![GridCell::new(name.get_raw_string())];
+        let required: GridCell = match requirement {
+            Some(requirement) => {
+                let specifier: String = requirement.get_specifier_string();
+                let specifier: &str = if specifier.is_empty() {
+                    "any"
+                } else {
+                    &specifier
+                };
+                GridCell::new(specifier)
+            }
+            None => GridCell::new_colored("-", RedANSI),
+        };
+        row.push(required);
+
+        for version in versions {
+            let cell: GridCell = match (requirement, version) {
+                (_, None) => GridCell::new_colored("-", RedANSI),
+                (None, Some(version)) => GridCell::new(&version.get_string()),
+                (Some(requirement), Some(version)) => {
+                    self.get_requirement_cell(requirement, version)
+                }
+            };
+            row.push(cell);
+        }
+        Some(row)
+    }
+
+    fn get_requirement_cell(&self, requirement: &PipRequirement, version: &PipVersion) -> GridCell {
+        let version_string: String = version.get_string();
+        if self.is_satisfied(requirement, version) {
+            return GridCell::new(&version_string);
+        }
+
+        // Rejected versions are ranked against the first version the requirement names.
+        let reference: Option<PipVersion> = requirement
+            .get_specifiers()
+            .first()
+            .and_then(|specifier| PipVersion::from_string(specifier.get_version()));
+        match reference {
+            Some(reference) if *version > reference => {
+                GridCell::new_colored(&version_string, GreenANSI)
+            }
+            _ => GridCell::new_colored(&version_string, YellowANSI),
+        }
+    }
+
+    fn is_satisfied(&self, requirement: &PipRequirement, version: &PipVersion) -> bool {
+        requirement
+            .get_specifiers()
+            .iter()
+            .all(|specifier| specifier.contains(version))
+    }
+}
+
+impl Default for PipComparison {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,797 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::pep440::PipVersion;
+use super::pep508::PipRequirement;
+use super::pip::{PipMetadata, PipPackage, PipPackageName};
+
+use crate::terminal::Terminal;
+use crate::terminal::{CyanANSI, GreenANSI, RedANSI, WhiteANSI, YellowANSI};
+
+pub struct PipConflict {
+    package: PipPackage,
+    requirement: PipRequirement,
+    installed: Option<PipVersion>,
+}
+
+impl PipConflict {
+    pub fn new(
+        package: &PipPackage,
+        requirement: &PipRequirement,
+        installed: Option<PipVersion>,
+    ) -> Self {
+        let package: PipPackage = package.clone();
+        let requirement: PipRequirement = requirement.clone();
+        PipConflict {
+            package,
+            requirement,
+            installed,
+        }
+    }
+
+    pub fn get_package(&self) -> &PipPackage {
+        &self.package
+    }
+
+    pub fn get_requirement(&self) -> &PipRequirement {
+        &self.requirement
+    }
+
+    pub fn get_installed(&self) -> Option<&PipVersion> {
+        self.installed.as_ref()
+    }
+
+    pub fn is_missing(&self) -> bool {
+        self.installed.is_none()
+    }
+
+    pub fn get_string(&self) -> String {
+        let package_name: &str = self.package.get_name().get_raw_string();
+        let package_version: String = self.package.get_version().get_string();
+        let name: &str = self.requirement.get_name().get_raw_string();
+        let specifier: String = self.requirement.get_specifier_string();
+
+        match &self.installed {
+            Some(installed) => format!(
+                "{} {} has requirement {}{}, but {} {} is installed",
+                package_name,
+                package_version,
+                name,
+                specifier,
+                name,
+                installed.get_string()
+            ),
+            None => format!(
+                "{} {} requires {}{}, which is not installed",
+                package_name, package_version, name, specifier
+            ),
+        }
+    }
+}
+
+pub struct PipDependencyGraph {
+    packages: BTreeMap<PipPackageName, PipPackage>,
+    requirements: HashMap<PipPackageName, Vec<PipRequirement>>,
+    extras: HashMap<PipPackageName, HashSet<String>>,
+    terminal: Terminal,
+}
+
+impl PipDependencyGraph {
+    pub fn new(packages: &[PipPackage], metadata: &[PipMetadata]) -> Self {
+        let mut package_map: BTreeMap<PipPackageName, PipPackage> = BTreeMap::new();
+        for package in packages.iter() {
+            package_map.insert(package.get_name().clone(), package.clone());
+        }
+
+        let mut requirements: HashMap<PipPackageName, Vec<PipRequirement>> = HashMap::new();
+        for meta in metadata.iter() {
+            let name: PipPackageName = meta.get_package().get_name().clone();
+            requirements.insert(name, meta.get_requirements().clone());
+        }
+
+        let mut graph: PipDependencyGraph = PipDependencyGraph {
+            packages: package_map,
+            requirements,
+            extras: HashMap::new(),
+            terminal: Terminal::new(),
+        };
+        graph.activate_extras();
+        graph
+    }
+
+    pub fn get_package(&self, name: &PipPackageName) -> Option<&PipPackage> {
+        self.packages.get(name)
+    }
+
+    pub fn get_package_names(&self) -> Vec<PipPackageName> {
+        self.packages.keys().cloned().collect()
+    }
+
+    /// Returns the extras of a package that other installed packages ask for.
+    pub fn get_activated_extras(&self, name: &PipPackageName) -> Option<&HashSet<String>> {
+        self.extras.get(name)
+    }
+
+    /// Returns every requirement of an extra, regardless of whether it was activated.
+    pub fn get_extra_requirements(
+        &self,
+        name: &PipPackageName,
+        extra: &str,
+    ) -> Vec<PipRequirement> {
+        let requirements: Option<&Vec<PipRequirement>> = self.requirements.get(name);
+        requirements
+            .map(|requirements| {
+                requirements
+                    .iter()
+                    .filter(|requirement| {
+                        requirement.is_required_by(Some(extra)) && !requirement.is_required_by(None)
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the base requirements of a package plus those of its activated extras.
+    pub fn get_dependencies(&self, name: &PipPackageName) -> Vec<PipRequirement> {
+        let mut dependencies: Vec<PipRequirement> = Vec::new();
+
+        for requirement in self.get_active_requirements(name) {
+            let is_duplicate: bool = dependencies
+                .iter()
+                .any(|dependency| dependency.get_name() == requirement.get_name());
+
+            if !is_duplicate {
+                dependencies.push(requirement.clone());
+            }
+        }
+        dependencies.sort_by(|a, b| a.get_name().get_string().cmp(b.get_name().get_string()));
+        dependencies
+    }
+
+    pub fn get_dependents(&self, name: &PipPackageName) -> Vec<PipPackageName> {
+        self.packages
+            .keys()
+            .filter(|package| {
+                self.get_dependencies(package)
+                    .iter()
+                    .any(|dependency| dependency.get_name() == name)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns installed packages that no other installed package depends on.
+    pub fn get_roots(&self) -> Vec<PipPackageName> {
+        let mut required: HashSet<PipPackageName> = HashSet::new();
+        for package in self.packages.keys() {
+            for dependency in self.get_dependencies(package) {
+                if dependency.get_name() != package {
+                    required.insert(dependency.get_name().clone());
+                }
+            }
+        }
+
+        self.packages
+            .keys()
+            .filter(|package| !required.contains(package))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns every package reachable from `name`, including itself.
+    pub fn get_closure(&self, name: &PipPackageName) -> HashSet<PipPackageName> {
+        let mut closure: HashSet<PipPackageName> = HashSet::new();
+        let mut stack: Vec<PipPackageName> = vec![name.clone()];
+
+        while let Some(package) = stack.pop() {
+            if !self.packages.contains_key(&package) || !closure.insert(package.clone()) {
+                continue;
+            }
+            for dependency in self.get_dependencies(&package) {
+                stack.push(dependency.get_name().clone());
+            }
+        }
+        closure
+    }
+
+    /// Returns every active requirement that is either not installed or whose
+    /// specifiers reject the installed version.
+    pub fn get_conflicts(&self) -> Vec<PipConflict> {
+        let mut conflicts: Vec<PipConflict> = Vec::new();
+
+        for package in self.packages.values() {
+            for requirement in self.get_active_requirements(package.get_name()) {
+                let installed: Option<&PipPackage> = self.packages.get(requirement.get_name());
+                let is_satisfied: bool = installed.is_some_and(|installed| {
+                    requirement
+                        .get_specifiers()
+                        .iter()
+                        .all(|specifier| specifier.contains(installed.get_version()))
+                });
+
+                if !is_satisfied {
+                    let installed: Option<PipVersion> =
+                        installed.map(|installed| installed.get_version().clone());
+                    let conflict: PipConflict = PipConflict::new(package, requirement, installed);
+                    conflicts.push(conflict);
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Returns the smallest set of packages whose transitive closure is the installed set.
+    ///
+    /// Every strongly connected component that no other component depends on needs one
+    /// representative. An extra of such a representative is requested when all of its
+    /// requirements are installed and it accounts for another otherwise top-level package.
+    pub fn get_top_level(&mut self) -> Vec<PipPackage> {
+        let mut root_extras: HashMap<PipPackageName, Vec<String>> = HashMap::new();
+
+        loop {
+            let sources: Vec<Vec<PipPackageName>> = self.get_source_components();
+            let source_members: HashSet<&PipPackageName> = sources.iter().flatten().collect();
+            let mut activated: Vec<(PipPackageName, String)> = Vec::new();
+
+            for component in sources.iter() {
+                let root: &PipPackageName = &component[0];
+                for extra in self.get_declared_extras(root) {
+                    let requirements: Vec<PipRequirement> =
+                        self.get_extra_requirements(root, &extra);
+                    let is_active: bool = self
+                        .extras
+                        .get(root)
+                        .is_some_and(|extras| extras.contains(&extra));
+                    let is_installed: bool = requirements
+                        .iter()
+                        .all(|requirement| self.packages.contains_key(requirement.get_name()));
+                    let is_needed: bool = requirements.iter().any(|requirement| {
+                        let name: &PipPackageName = requirement.get_name();
+                        source_members.contains(name) && !component.contains(name)
+                    });
+
+                    if !is_active && is_installed && is_needed {
+                        activated.push((root.clone(), extra));
+                    }
+                }
+            }
+
+            if activated.is_empty() {
+                break;
+            }
+            for (root, extra) in activated {
+                self.extras
+                    .entry(root.clone())
+                    .or_default()
+                    .insert(extra.clone());
+                root_extras.entry(root).or_default().push(extra);
+            }
+            self.activate_extras();
+        }
+
+        let mut top_level: Vec<PipPackage> = Vec::new();
+        for component in self.get_source_components() {
+            let root: &PipPackageName = &component[0];
+            if let Some(package) = self.packages.get(root) {
+                let mut package: PipPackage = package.clone();
+                if let Some(extras) = root_extras.get(root) {
+                    package.set_extras(extras);
+                }
+                top_level.push(package);
+            }
+        }
+        top_level
+    }
+
+    pub fn print_tree(&self) {
+        for root in self.get_tree_roots().iter() {
+            self.write_package(root);
+            println!();
+
+            let mut path: Vec<PipPackageName> = vec![root.clone()];
+            self.print_dependencies(root, "", &mut path);
+        }
+
+        self.print_missing();
+    }
+
+    pub fn print_reverse(&self, name: &PipPackageName) {
+        if !self.packages.contains_key(name) {
+            let string: String = format!("Package is not installed: {}", name.get_raw_string());
+            self.terminal.writeln_ansi(&string, &RedANSI);
+            return;
+        }
+
+        self.write_package(name);
+        println!();
+
+        let mut path: Vec<PipPackageName> = vec![name.clone()];
+        self.print_dependents(name, "", &mut path);
+    }
+}
+
+impl PipDependencyGraph {
+    fn get_active_requirements(&self, name: &PipPackageName) -> Vec<&PipRequirement> {
+        let requirements: Option<&Vec<PipRequirement>> = self.requirements.get(name);
+        let extras: Option<&HashSet<String>> = self.extras.get(name);
+
+        let mut active: Vec<&PipRequirement> = Vec::new();
+        if let Some(requirements) = requirements {
+            for requirement in requirements.iter() {
+                let is_active: bool = requirement.is_required_by(None)
+                    || extras.is_some_and(|extras| {
+                        extras
+                            .iter()
+                            .any(|extra| requirement.is_required_by(Some(extra)))
+                    });
+                if is_active {
+                    active.push(requirement);
+                }
+            }
+        }
+        active
+    }
+
+    /// Returns the components of the condensed graph that nothing else depends on.
+    fn get_source_components(&self) -> Vec<Vec<PipPackageName>> {
+        let components: Vec<Vec<PipPackageName>> = self.get_components();
+
+        let mut component_index: HashMap<&PipPackageName, usize> = HashMap::new();
+        for (idx, component) in components.iter().enumerate() {
+            for package in component.iter() {
+                component_index.insert(package, idx);
+            }
+        }
+
+        let mut has_dependents: Vec<bool> = vec![false; components.len()];
+        for (idx, component) in components.iter().enumerate() {
+            for package in component.iter() {
+                for dependency in self.get_dependencies(package) {
+                    let target: Option<&usize> = component_index.get(dependency.get_name());
+                    if let Some(&target) = target {
+                        if target != idx {
+                            has_dependents[target] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        components
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| !has_dependents[*idx])
+            .map(|(_, component)| component)
+            .collect()
+    }
+
+    /// Splits the installed packages into strongly connected components (Tarjan).
+    fn get_components(&self) -> Vec<Vec<PipPackageName>> {
+        let mut search: ComponentSearch = ComponentSearch::new();
+        for package in self.packages.keys() {
+            if !search.indices.contains_key(package) {
+                self.connect_component(package, &mut search);
+            }
+        }
+
+        let mut components: Vec<Vec<PipPackageName>> = search.components;
+        for component in components.iter_mut() {
+            component.sort();
+        }
+        components.sort_by(|a, b| a[0].cmp(&b[0]));
+        components
+    }
+
+    fn connect_component(&self, package: &PipPackageName, search: &mut ComponentSearch) {
+        let index: usize = search.index;
+        search.indices.insert(package.clone(), index);
+        search.lowlinks.insert(package.clone(), index);
+        search.index += 1;
+        search.stack.push(package.clone());
+        search.on_stack.insert(package.clone());
+
+        for dependency in self.get_dependencies(package) {
+            let name: &PipPackageName = dependency.get_name();
+            if !self.packages.contains_key(name) {
+                continue;
+            }
+
+            if !search.indices.contains_key(name) {
+                self.connect_component(name, search);
+                let lowlink: usize = search.lowlinks[package].min(search.lowlinks[name]);
+                search.lowlinks.insert(package.clone(), lowlink);
+            } else if search.on_stack.contains(name) {
+                let lowlink: usize = search.lowlinks[package].min(search.indices[name]);
+                search.lowlinks.insert(package.clone(), lowlink);
+            }
+        }
+
+        if search.lowlinks[package] == search.indices[package] {
+            let mut component: Vec<PipPackageName> = Vec::new();
+            while let Some(member) = search.stack.pop() {
+                search.on_stack.remove(&member);
+                let is_root: bool = &member == package;
+                component.push(member);
+                if is_root {
+                    break;
+                }
+            }
+            search.components.push(component);
+        }
+    }
+
+    fn get_declared_extras(&self, name: &PipPackageName) -> Vec<String> {
+        let mut extras: Vec<String> = self
+            .requirements
+            .get(name)
+            .map(|requirements| {
+                requirements
+                    .iter()
+                    .flat_map(|requirement| requirement.get_marker_extras())
+                    .collect()
+            })
+            .unwrap_or_default();
+        extras.sort();
+        extras.dedup();
+        extras
+    }
+
+    fn get_tree_roots(&self) -> Vec<PipPackageName> {
+        let mut roots: Vec<PipPackageName> = self.get_roots();
+        let mut visited: HashSet<PipPackageName> = HashSet::new();
+        for root in roots.iter() {
+            visited.extend(self.get_closure(root));
+        }
+
+        // Packages that only depend on each other are unreachable from any root.
+        for package in self.packages.keys() {
+            if !visited.contains(package) {
+                visited.extend(self.get_closure(package));
+                roots.push(package.clone());
+            }
+        }
+        roots
+    }
+
+    fn activate_extras(&mut self) {
+        let mut changed: bool = true;
+        while changed {
+            changed = false;
+            let package_names: Vec<PipPackageName> = self.get_package_names();
+
+            for package in package_names.iter() {
+                for dependency in self.get_dependencies(package) {
+                    let extras: &mut HashSet<String> = self
+                        .extras
+                        .entry(dependency.get_name().clone())
+                        .or_default();
+                    for extra in dependency.get_extras() {
+                        changed |= extras.insert(extra.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn print_dependencies(
+        &self,
+        name: &PipPackageName,
+        indent: &str,
+        path: &mut Vec<PipPackageName>,
+    ) {
+        let dependencies: Vec<PipRequirement> = self.get_dependencies(name);
+
+        for (idx, dependency) in dependencies.iter().enumerate() {
+            let is_last: bool = idx == dependencies.len() - 1;
+            let dependency_name: &PipPackageName = dependency.get_name();
+
+            self.write_branch(indent, is_last);
+            self.write_requirement(dependency);
+
+            if path.contains(dependency_name) {
+                self.terminal.writeln_ansi(" [cycle]", &YellowANSI);
+                continue;
+            }
+            println!();
+
+            if self.packages.contains_key(dependency_name) {
+                let indent: String = self.get_child_indent(indent, is_last);
+                path.push(dependency_name.clone());
+                self.print_dependencies(dependency_name, &indent, path);
+                path.pop();
+            }
+        }
+    }
+
+    fn print_dependents(
+        &self,
+        name: &PipPackageName,
+        indent: &str,
+        path: &mut Vec<PipPackageName>,
+    ) {
+        let dependents: Vec<PipPackageName> = self.get_dependents(name);
+
+        for (idx, dependent) in dependents.iter().enumerate() {
+            let is_last: bool = idx == dependents.len() - 1;
+
+            self.write_branch(indent, is_last);
+            self.write_package(dependent);
+
+            let requirement: Option<PipRequirement> = self
+                .get_dependencies(dependent)
+                .into_iter()
+                .find(|dependency| dependency.get_name() == name);
+            if let Some(requirement) = requirement {
+                let specifier: String = self.get_specifier_string(&requirement);
+                let string: String = format!(" [requires: {}]", specifier);
+                self.terminal.write_ansi(&string, &CyanANSI);
+            }
+
+            if path.contains(dependent) {
+                self.terminal.writeln_ansi(" [cycle]", &YellowANSI);
+                continue;
+            }
+            println!();
+
+            let indent: String = self.get_child_indent(indent, is_last);
+            path.push(dependent.clone());
+            self.print_dependents(dependent, &indent, path);
+            path.pop();
+        }
+    }
+
+    fn print_missing(&self) {
+        let mut missing: Vec<(PipPackageName, PipRequirement)> = Vec::new();
+        for package in self.packages.keys() {
+            for dependency in self.get_dependencies(package) {
+                if !self.packages.contains_key(dependency.get_name()) {
+                    missing.push((package.clone(), dependency));
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return;
+        }
+
+        self.terminal
+            .writeln_ansi("\nMissing Dependencies:", &RedANSI);
+        for (package, dependency) in missing.iter() {
+            let specifier: String = self.get_specifier_string(dependency);
+            let string: String = format!(
+                "{} requires {} [{}]",
+                package.get_raw_string(),
+                dependency.get_name().get_raw_string(),
+                specifier
+            );
+            self.terminal.writeln_ansi(&string, &RedANSI);
+        }
+    }
+
+    fn write_package(&self, name: &PipPackageName) {
+        if let Some(package) = self.packages.get(name) {
+            let string: String = format!(
+                "{}=={}",
+                package.get_name().get_raw_string(),
+                package.get_version().get_string()
+            );
+            self.terminal.write_ansi(&string, &GreenANSI);
+        }
+    }
+
+    fn write_requirement(&self, requirement: &PipRequirement) {
+        let name: &PipPackageName = requirement.get_name();
+        let specifier: String = self.get_specifier_string(requirement);
+        let package: Option<&PipPackage> = self.packages.get(name);
+
+        self.terminal.write_ansi(name.get_raw_string(), &GreenANSI);
+        match package {
+            Some(package) => {
+                let string: String = format!(
+                    " [required: {}, installed: {}]",
+                    specifier,
+                    package.get_version().get_string()
+                );
+                self.terminal.write_ansi(&string, &CyanANSI);
+            }
+            None => {
+                let string: String = format!(" [required: {}] [missing]", specifier);
+                self.terminal.write_ansi(&string, &RedANSI);
+            }
+        }
+    }
+
+    fn write_branch(&self, indent: &str, is_last: bool) {
+        let branch: &str = if is_last { "└── " } else { "├── " };
+        let string: String = format!("{}{}", indent, branch);
+        self.terminal.write_ansi(&string, &WhiteANSI);
+    }
+
+    fn get_child_indent(&self, indent: &str, is_last: bool) -> String {
+        let continuation: &str = if is_last { "    " } else { "│   " };
+        format!("{}{}", indent, continuation)
+    }
+
+    fn get_specifier_string(&self, requirement: &PipRequirement) -> String {
+        let specifier: String = requirement.get_specifier_string();
+        if specifier.is_empty() {
+            return "any".to_string();
+        }
+        specifier
+    }
+}
+
+struct ComponentSearch {
+    index: usize,
+    indices: HashMap<PipPackageName, usize>,
+    lowlinks: HashMap<PipPackageName, usize>,
+    stack: Vec<PipPackageName>,
+    on_stack: HashSet<PipPackageName>,
+    components: Vec<Vec<PipPackageName>>,
+}
+
+impl ComponentSearch {
+    fn new() -> Self {
+        ComponentSearch {
+            index: 0,
+            indices: HashMap::new(),
+            lowlinks: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            components: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::misc::path::WPath;
+
+    fn get_package(name: &str, version: &str) -> PipPackage {
+        let name: PipPackageName = PipPackageName::new(name);
+        let version: PipVersion = PipVersion::from_string_or_legacy(version);
+        PipPackage::new(name, version, WPath::from_string(""))
+    }
+
+    fn get_metadata(package: &PipPackage, requirements: &[&str]) -> PipMetadata {
+        let requirements: Vec<PipRequirement> = requirements
+            .iter()
+            .map(|string| PipRequirement::from_string(string).unwrap())
+            .collect();
+        PipMetadata::new(package, &requirements)
+    }
+
+    fn get_graph(packages: &[(&str, &str, &[&str])]) -> PipDependencyGraph {
+        let mut installed: Vec<PipPackage> = Vec::new();
+        let mut metadata: Vec<PipMetadata> = Vec::new();
+        for (name, version, requirements) in packages.iter() {
+            let package: PipPackage = get_package(name, version);
+            metadata.push(get_metadata(&package, requirements));
+            installed.push(package);
+        }
+        PipDependencyGraph::new(&installed, &metadata)
+    }
+
+    fn get_top_level_strings(graph: &mut PipDependencyGraph) -> Vec<String> {
+        graph
+            .get_top_level()
+            .iter()
+            .map(|package| {
+                let name: &str = package.get_name().get_string();
+                if package.get_extras().is_empty() {
+                    return name.to_string();
+                }
+                format!("{}[{}]", name, package.get_extras().join(","))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_one_representative_of_a_cycle() {
+        let mut graph: PipDependencyGraph = get_graph(&[
+            ("alpha", "1.0", &["beta"]),
+            ("beta", "1.0", &["alpha", "gamma"]),
+            ("gamma", "1.0", &[]),
+        ]);
+        assert_eq!(get_top_level_strings(&mut graph), vec!["alpha"]);
+
+        let mut graph: PipDependencyGraph = get_graph(&[
+            ("alpha", "1.0", &["beta"]),
+            ("beta", "1.0", &["alpha"]),
+            ("app", "1.0", &["beta"]),
+        ]);
+        assert_eq!(get_top_level_strings(&mut graph), vec!["app"]);
+    }
+
+    #[test]
+    fn requests_an_extra_that_absorbs_a_top_level_package() {
+        let mut graph: PipDependencyGraph = get_graph(&[
+            (
+                "requests",
+                "2.32.0",
+                &["idna", r#"PySocks; extra == "socks""#],
+            ),
+            ("idna", "3.7", &[]),
+            ("pysocks", "1.7.1", &[]),
+        ]);
+        assert_eq!(get_top_level_strings(&mut graph), vec!["requests[socks]"]);
+
+        let mut graph: PipDependencyGraph = get_graph(&[
+            (
+                "requests",
+                "2.32.0",
+                &[
+                    r#"PySocks; extra == "socks""#,
+                    r#"chardet; extra == "socks""#,
+                ],
+            ),
+            ("pysocks", "1.7.1", &[]),
+        ]);
+        assert_eq!(
+            get_top_level_strings(&mut graph),
+            vec!["pysocks", "requests"]
+        );
+    }
+
+    #[test]
+    fn requests_every_extra_of_an_or_chain() {
+        let mut graph: PipDependencyGraph = get_graph(&[
+            (
+                "requests",
+                "2.32.0",
+                &[r#"PySocks; extra == "socks" or extra == "all""#],
+            ),
+            ("pysocks", "1.7.1", &[]),
+        ]);
+        let top_level: Vec<String> = get_top_level_strings(&mut graph);
+        assert_eq!(top_level, vec!["requests[all,socks]"]);
+    }
+
+    #[test]
+    fn reports_missing_and_conflicting_requirements() {
+        let graph: PipDependencyGraph = get_graph(&[
+            ("app", "1.0", &["missing>=1.0", "library<2", "tool>=1"]),
+            ("library", "2.1", &[]),
+            ("tool", "1.4", &[r#"plugin; extra == "extras""#]),
+        ]);
+        let conflicts: Vec<PipConflict> = graph.get_conflicts();
+        assert_eq!(conflicts.len(), 2);
+
+        let missing: &PipConflict = &conflicts[0];
+        assert!(missing.is_missing());
+        assert_eq!(missing.get_package().get_name().get_string(), "app");
+        assert_eq!(missing.get_requirement().get_name().get_string(), "missing");
+
+        let conflict: &PipConflict = &conflicts[1];
+        assert!(!conflict.is_missing());
+        assert_eq!(
+            conflict.get_requirement().get_name().get_string(),
+            "library"
+        );
+        let installed: Option<String> =
+            conflict.get_installed().map(|version| version.get_string());
+        assert_eq!(installed.as_deref(), Some("2.1"));
+    }
+
+    #[test]
+    fn checks_requirements_of_activated_extras() {
+        let graph: PipDependencyGraph = get_graph(&[
+            ("app", "1.0", &["tool[extras]"]),
+            (
+                "tool",
+                "1.4",
+                &[r#"plugin>=2; extra == "all" or extra == "extras""#],
+            ),
+            ("plugin", "1.0", &[]),
+        ]);
+        let conflicts: Vec<PipConflict> = graph.get_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].get_package().get_name().get_string(), "tool");
+        assert_eq!(
+            conflicts[0].get_installed(),
+            Some(&PipVersion::from_string_or_legacy("1.0"))
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use std::fs;
+use std::fs::ReadDir;
+use std::io;
+use std::path::PathBuf;
+
+use regex::Regex;
+
+use super::python::PythonEnvironment;
+use super::virtualenv::{VirtualEnvCFG, VirtualEnvCreator};
+
+use crate::misc::path::WPath;
+use crate::misc::table_display::DynamicTable;
+use crate::misc::version::SemanticVersion;
+
+#[derive(Debug, Clone)]
+pub enum VirtualEnvIssue {
+    MissingSetting(String),
+    MissingHome(WPath),
+    MissingBaseExecutable(WPath),
+    VersionMismatch(SemanticVersion, SemanticVersion),
+    StaleShebang(WPath, String),
+    BrokenActivate(WPath, String),
+}
+
+impl VirtualEnvIssue {
+    pub fn get_string(&self) -> String {
+        match self {
+            VirtualEnvIssue::MissingSetting(name) => format!("Missing setting: {}", name),
+            VirtualEnvIssue::MissingHome(path) => {
+                format!("Missing home: {}", path.get_path_buf().display())
+            }
+            VirtualEnvIssue::MissingBaseExecutable(path) => {
+                format!("Missing base executable: {}", path.get_path_buf().display())
+            }
+            VirtualEnvIssue::VersionMismatch(expected, found) => format!(
+                "Version mismatch: expected {}, found {}",
+                expected.get_3p_string(),
+                found.get_3p_string()
+            ),
+            VirtualEnvIssue::StaleShebang(script, interpreter) => {
+                format!("Stale shebang: {:?} -> {}", script, interpreter)
+            }
+            VirtualEnvIssue::BrokenActivate(script, path) => {
+                format!("Broken activate path: {:?} -> {}", script, path)
+            }
+        }
+    }
+}
+
+pub struct VirtualEnvDiagnosis {
+    environment_directory: WPath,
+    version: Option<SemanticVersion>,
+    creator: VirtualEnvCreator,
+    issues: Vec<VirtualEnvIssue>,
+}
+
+impl VirtualEnvDiagnosis {
+    pub fn new(venv_cfg: &VirtualEnvCFG) -> Self {
+        let environment_directory: WPath = venv_cfg.get_environment_directory();
+        let version: Option<SemanticVersion> = venv_cfg.version_info.clone();
+        let creator: VirtualEnvCreator = venv_cfg.get_creator();
+        let mut issues: Vec<VirtualEnvIssue> = Vec::new();
+
+        Self::diagnose_interpreter(venv_cfg, &mut issues);
+        Self::diagnose_scripts(venv_cfg, &mut issues);
+
+        VirtualEnvDiagnosis {
+            environment_directory,
+            version,
+            creator,
+            issues,
+        }
+    }
+
+    pub fn get_environment_directory(&self) -> &WPath {
+        &self.environment_directory
+    }
+
+    pub fn get_issues(&self) -> &Vec<VirtualEnvIssue> {
+        &self.issues
+    }
+
+    pub fn needs_repair(&self) -> bool {
+        !self.issues.is_empty()
+    }
+
+    pub fn print(&self) {
+        let mut table: DynamicTable = DynamicTable::new(0.8, 1);
+        table.set_header("Environment Diagnosis");
+        table.add_fmt_parameter("Path", &self.environment_directory);
+        table.add_string_parameter("Creator", self.creator.get_string());
+
+        if let Some(version) = &self.version {
+            table.add_string_parameter("Version", version.get_3p_string());
+        }
+
+        for (idx, issue) in self.issues.iter().enumerate() {
+            let attribute: String = format!("Issue {}", idx + 1);
+            table.add_string_parameter(attribute, issue.get_string());
+        }
+
+        let status: &str = if self.needs_repair() {
+            "Needs Repair"
+        } else {
+            "Healthy"
+        };
+        table.add_string_parameter("Status", status);
+        table.print();
+    }
+}
+
+impl VirtualEnvDiagnosis {
+    fn diagnose_interpreter(venv_cfg: &VirtualEnvCFG, issues: &mut Vec<VirtualEnvIssue>) {
+        if let Some(home) = &venv_cfg.home {
+            if !home.exists() {
+                issues.push(VirtualEnvIssue::MissingHome(home.clone()));
+            }
+        } else {
+            issues.push(VirtualEnvIssue::MissingSetting("home".to_string()));
+        }
+
+        if let Some(base_executable) = &venv_cfg.base_executable {
+            if !base_executable.exists() {
+                let base_executable: WPath = base_executable.clone();
+                issues.push(VirtualEnvIssue::MissingBaseExecutable(base_executable));
+                return;
+            }
+
+            let found: Option<SemanticVersion> =
+                PythonEnvironment::get_executable_version(base_executable);
+            if let (Some(found), Some(expected)) = (found, &venv_cfg.version_info) {
+                if found.get_3p_version() != expected.get_3p_version() {
+                    let expected: SemanticVersion = expected.clone();
+                    issues.push(VirtualEnvIssue::VersionMismatch(expected, found));
+                }
+            }
+        }
+    }
+
+    fn diagnose_scripts(venv_cfg: &VirtualEnvCFG, issues: &mut Vec<VirtualEnvIssue>) {
+        let scripts_directory: WPath = venv_cfg.get_scripts_directory();
+        let environment_strings: Vec<String> =
+            Self::get_path_strings(&venv_cfg.get_environment_directory());
+        let read_dir: Result<ReadDir, io::Error> = scripts_directory.read_dir();
+
+        if let Ok(read_dir) = read_dir {
+            for entry in read_dir.flatten() {
+                let entry_path: PathBuf = entry.path();
+                if !entry_path.is_file() {
+                    continue;
+                }
+
+                let script: WPath = WPath::from_path_buf(&entry_path);
+                let file_name: String = entry.file_name().to_string_lossy().to_lowercase();
+
+                if file_name.starts_with("activate") {
+                    Self::diagnose_activate(&script, &environment_strings, issues);
+                } else {
+                    Self::diagnose_shebang(&script, &environment_strings, issues);
+                }
+            }
+        }
+    }
+
+    fn diagnose_shebang(
+        script: &WPath,
+        environments: &[String],
+        issues: &mut Vec<VirtualEnvIssue>,
+    ) {
+        let bytes: Result<Vec<u8>, io::Error> = fs::read(script);
+        if let Ok(bytes) = bytes {
+            let shebang: Option<String> = Self::find_shebang(&bytes);
+            if let Some(shebang) = shebang {
+                let interpreter: String = Self::get_shebang_interpreter(&shebang);
+                if !interpreter.to_lowercase().contains("python") {
+                    return;
+                }
+
+                let normalized: String = Self::normalize_path_string(&interpreter);
+                let is_within: bool = environments
+                    .iter()
+                    .any(|environment| Self::is_within_directory(&normalized, environment));
+                if !is_within {
+                    let script: WPath = script.clone();
+                    issues.push(VirtualEnvIssue::StaleShebang(script, interpreter));
+                }
+            }
+        }
+    }
+
+    fn diagnose_activate(
+        script: &WPath,
+        environments: &[String],
+        issues: &mut Vec<VirtualEnvIssue>,
+    ) {
+        let content: Result<String, io::Error> = fs::read_to_string(script);
+        let regex: Regex =
+            Regex::new(r#"VIRTUAL_ENV["']?[\s=]+["']?([A-Za-z]:[\\/][^"'\r\n]*|/[^"'\r\n]*)"#)
+                .unwrap();
+
+        if let Ok(content) = content {
+            for capture in regex.captures_iter(&content) {
+                let path: &str = capture[1].trim();
+                let normalized: String = Self::normalize_path_string(path);
+                if !environments.contains(&normalized) {
+                    let script: WPath = script.clone();
+                    let path: String = path.to_string();
+                    issues.push(VirtualEnvIssue::BrokenActivate(script, path));
+                    return;
+                }
+            }
+        }
+    }
+
+    fn find_shebang(bytes: &[u8]) -> Option<String> {
+        if bytes.starts_with(b"#!") {
+            let end: usize = bytes
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .unwrap_or(bytes.len());
+            return Some(String::from_utf8_lossy(&bytes[..end]).to_string());
+        }
+
+        // Windows console-script launchers embed the shebang right before the appended zip archive.
+        for (start, window) in bytes.windows(2).enumerate() {
+            if window != b"#!" {
+                continue;
+            }
+
+            let end: Option<usize> = bytes[start..].iter().position(|&byte| byte == b'\n');
+            if let Some(end) = end {
+                let end: usize = start + end;
+                if bytes[end + 1..].starts_with(b"PK\x03\x04") {
+                    return Some(String::from_utf8_lossy(&bytes[start..end]).to_string());
+                }
+            }
+        }
+        None
+    }
+
+    fn get_shebang_interpreter(shebang: &str) -> String {
+        let shebang: &str = shebang.trim_start_matches("#!").trim();
+        if let Some(quoted) = shebang.strip_prefix('"') {
+            let interpreter: &str = quoted.split('"').next().unwrap_or_default();
+            return interpreter.to_string();
+        }
+
+        let interpreter: &str = shebang.split_whitespace().next().unwrap_or_default();
+        interpreter.to_string()
+    }
+
+    /// Returns the path as given and its canonical form, since scripts record whichever
+    /// path the environment was created through, e.g. `/tmp` rather than `/private/tmp`.
+    fn get_path_strings(path: &WPath) -> Vec<String> {
+        let raw: String = path.get_path_buf().to_string_lossy().to_string();
+        let mut strings: Vec<String> = vec![Self::normalize_path_string(&raw)];
+
+        if let Some(canonical) = path.get_canonical_string() {
+            let canonical: String = Self::normalize_path_string(&canonical);
+            if !strings.contains(&canonical) {
+                strings.push(canonical);
+            }
+        }
+        strings
+    }
+
+    fn is_within_directory(path: &str, directory: &str) -> bool {
+        let remainder: Option<&str> = path.strip_prefix(directory);
+        if let Some(remainder) = remainder {
+            return remainder.starts_with(['/', '\\']);
+        }
+        false
+    }
+
+    fn normalize_path_string(path: &str) -> String {
+        let path: &str = path.trim_end_matches(['/', '\\']);
+        if cfg!(windows) {
+            return path.replace('/', "\\").to_lowercase();
+        }
+        path.to_string()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::symlink;
+    use std::path::Path;
+    use std::process;
+
+    use super::*;
+
+    /// Creates an environment under `<root>/real` that scripts reach through the
+    /// `<root>/link` symlink, and returns the root and the link.
+    fn create_linked_environment(name: &str, shebang: &str) -> (PathBuf, PathBuf) {
+        let directory_name: String = format!("arranger-diagnosis-{}-{}", process::id(), name);
+        let root: PathBuf = std::env::temp_dir().join(directory_name);
+        let _ = fs::remove_dir_all(&root);
+
+        let real: PathBuf = root.join("real");
+        let link: PathBuf = root.join("link");
+        fs::create_dir_all(real.join("bin")).unwrap();
+        symlink(&real, &link).unwrap();
+
+        fs::write(real.join("pyvenv.cfg"), "version = 3.12.4\n").unwrap();
+        let shebang: String = shebang.replace("{env}", &link.display().to_string());
+        fs::write(real.join("bin").join("pip"), format!("#!{}\n", shebang)).unwrap();
+        let activate: String = format!("VIRTUAL_ENV='{}'\nexport VIRTUAL_ENV\n", link.display());
+        fs::write(real.join("bin").join("activate"), activate).unwrap();
+        (root, link)
+    }
+
+    fn get_script_issues(environment: &Path) -> Vec<VirtualEnvIssue> {
+        let cfg_file: WPath = WPath::from_path_buf(&environment.join("pyvenv.cfg"));
+        let venv_cfg: VirtualEnvCFG = VirtualEnvCFG::from_file(&cfg_file).unwrap();
+        let diagnosis: VirtualEnvDiagnosis = VirtualEnvDiagnosis::new(&venv_cfg);
+        diagnosis
+            .get_issues()
+            .iter()
+            .filter(|issue| {
+                matches!(
+                    issue,
+                    VirtualEnvIssue::StaleShebang(..) | VirtualEnvIssue::BrokenActivate(..)
+                )
+            })
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn accepts_scripts_written_through_a_symlink() {
+        let (root, link): (PathBuf, PathBuf) =
+            create_linked_environment("linked", "{env}/bin/python");
+        let issues: Vec<VirtualEnvIssue> = get_script_issues(&link);
+        fs::remove_dir_all(&root).unwrap();
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn flags_shebangs_outside_the_environment() {
+        let (root, link): (PathBuf, PathBuf) =
+            create_linked_environment("stale", "/old/env/bin/python");
+        let issues: Vec<VirtualEnvIssue> = get_script_issues(&link);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(
+            matches!(&issues[0], VirtualEnvIssue::StaleShebang(_, interpreter) if interpreter == "/old/env/bin/python")
+        );
+    }
+}
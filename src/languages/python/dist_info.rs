@@ -0,0 +1,367 @@
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+use std::sync::OnceLock;
+
+use serde_json::Value;
+
+use crate::misc::path::WPath;
+
+#[derive(Debug, Clone)]
+pub struct PipDirectUrl {
+    url: String,
+    editable: bool,
+    vcs: Option<String>,
+    commit_id: Option<String>,
+    archive_hashes: Vec<String>,
+}
+
+impl PipDirectUrl {
+    /// Parses the `direct_url.json` structure described by PEP 610.
+    pub fn from_string(string: &str) -> Option<Self> {
+        let json: Value = serde_json::from_str(string).ok()?;
+        let url: String = json.get("url")?.as_str()?.to_string();
+
+        let dir_info: Option<&Value> = json.get("dir_info");
+        let editable: bool = dir_info
+            .and_then(|dir_info| dir_info.get("editable"))
+            .and_then(|editable| editable.as_bool())
+            .unwrap_or(false);
+
+        let vcs_info: Option<&Value> = json.get("vcs_info");
+        let vcs: Option<String> = vcs_info
+            .and_then(|vcs_info| vcs_info.get("vcs"))
+            .and_then(|vcs| vcs.as_str())
+            .map(|vcs| vcs.to_string());
+        let commit_id: Option<String> = vcs_info
+            .and_then(|vcs_info| vcs_info.get("commit_id"))
+            .and_then(|commit_id| commit_id.as_str())
+            .map(|commit_id| commit_id.to_string());
+
+        let archive_info: Option<&Value> = json.get("archive_info");
+        let archive_hashes: Vec<String> = archive_info
+            .map(Self::get_archive_hashes_from_json)
+            .unwrap_or_default();
+
+        Some(PipDirectUrl {
+            url,
+            editable,
+            vcs,
+            commit_id,
+            archive_hashes,
+        })
+    }
+
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn is_editable(&self) -> bool {
+        self.editable
+    }
+
+    pub fn get_vcs(&self) -> Option<&str> {
+        self.vcs.as_deref()
+    }
+
+    pub fn get_commit_id(&self) -> Option<&str> {
+        self.commit_id.as_deref()
+    }
+
+    /// Returns the hashes of the installed archive in pip's `algorithm:digest` form.
+    pub fn get_archive_hashes(&self) -> &Vec<String> {
+        &self.archive_hashes
+    }
+
+    /// Returns the URL in the form pip accepts, e.g. `git+https://host/repo@commit`.
+    pub fn get_requirement_url(&self) -> String {
+        match (&self.vcs, &self.commit_id) {
+            (Some(vcs), Some(commit_id)) => format!("{}+{}@{}", vcs, self.url, commit_id),
+            (Some(vcs), None) => format!("{}+{}", vcs, self.url),
+            _ => self.url.clone(),
+        }
+    }
+}
+
+impl PipDirectUrl {
+    /// Reads `archive_info.hashes`, falling back to the deprecated `archive_info.hash`
+    /// which is written as `algorithm=digest`.
+    fn get_archive_hashes_from_json(archive_info: &Value) -> Vec<String> {
+        let mut archive_hashes: Vec<String> = Vec::new();
+        let hashes: Option<&serde_json::Map<String, Value>> = archive_info
+            .get("hashes")
+            .and_then(|hashes| hashes.as_object());
+        if let Some(hashes) = hashes {
+            for (algorithm, digest) in hashes.iter() {
+                if let Some(digest) = digest.as_str() {
+                    archive_hashes.push(format!("{}:{}", algorithm, digest));
+                }
+            }
+        }
+
+        let legacy_hash: Option<&str> = archive_info.get("hash").and_then(|hash| hash.as_str());
+        if let Some((algorithm, digest)) = legacy_hash.and_then(|hash| hash.split_once('=')) {
+            let archive_hash: String = format!("{}:{}", algorithm, digest);
+            if !archive_hashes.contains(&archive_hash) {
+                archive_hashes.push(archive_hash);
+            }
+        }
+        archive_hashes
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PipRecordEntry {
+    path: String,
+    hash: Option<String>,
+    size: Option<u64>,
+}
+
+impl PipRecordEntry {
+    /// Parses a `path,hash,size` line of a RECORD file.
+    pub fn from_string(string: &str) -> Option<Self> {
+        let fields: Vec<String> = Self::split_fields(string);
+        let path: String = fields.first()?.to_string();
+        if path.is_empty() {
+            return None;
+        }
+
+        let hash: Option<String> = fields
+            .get(1)
+            .filter(|hash| !hash.is_empty())
+            .map(|hash| hash.to_string());
+        let size: Option<u64> = fields.get(2).and_then(|size| size.parse::<u64>().ok());
+        Some(PipRecordEntry { path, hash, size })
+    }
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the hash as written in RECORD, e.g. `sha256=<urlsafe-base64>`.
+    pub fn get_hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    pub fn get_size(&self) -> Option<u64> {
+        self.size
+    }
+}
+
+impl PipRecordEntry {
+    fn split_fields(string: &str) -> Vec<String> {
+        let mut fields: Vec<String> = Vec::new();
+        let mut field: String = String::new();
+        let mut in_quotes: bool = false;
+        let mut chars: Peekable<Chars> = string.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            match char {
+                '"' if in_quotes && chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = !in_quotes,
+                ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+                char => field.push(char),
+            }
+        }
+        fields.push(field);
+        fields
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PipEntryPoint {
+    group: String,
+    name: String,
+    object_reference: String,
+}
+
+impl PipEntryPoint {
+    pub fn new(group: &str, name: &str, object_reference: &str) -> Self {
+        PipEntryPoint {
+            group: group.to_string(),
+            name: name.to_string(),
+            object_reference: object_reference.to_string(),
+        }
+    }
+
+    pub fn get_group(&self) -> &str {
+        &self.group
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the `module:attribute` the entry point resolves to.
+    pub fn get_object_reference(&self) -> &str {
+        &self.object_reference
+    }
+
+    pub fn is_script(&self) -> bool {
+        self.group == "console_scripts" || self.group == "gui_scripts"
+    }
+}
+
+/// Information installers leave next to METADATA in a `.dist-info` directory. Each
+/// file is only read the first time it is asked for, since listing an environment
+/// should not touch every RECORD in it.
+#[derive(Debug, Clone)]
+pub struct PipDistInfo {
+    dist_info_path: WPath,
+    installer: OnceLock<Option<String>>,
+    direct_url: OnceLock<Option<PipDirectUrl>>,
+    record: OnceLock<Vec<PipRecordEntry>>,
+    entry_points: OnceLock<Vec<PipEntryPoint>>,
+    size: OnceLock<u64>,
+}
+
+impl PipDistInfo {
+    pub fn new(dist_info_path: &WPath) -> Self {
+        PipDistInfo {
+            dist_info_path: dist_info_path.clone(),
+            installer: OnceLock::new(),
+            direct_url: OnceLock::new(),
+            record: OnceLock::new(),
+            entry_points: OnceLock::new(),
+            size: OnceLock::new(),
+        }
+    }
+
+    /// Returns the tool that installed the distribution, such as pip, conda or uv.
+    pub fn get_installer(&self) -> Option<&str> {
+        self.installer
+            .get_or_init(|| Self::read_installer(&self.dist_info_path))
+            .as_deref()
+    }
+
+    pub fn get_direct_url(&self) -> Option<&PipDirectUrl> {
+        self.direct_url
+            .get_or_init(|| Self::read_direct_url(&self.dist_info_path))
+            .as_ref()
+    }
+
+    pub fn is_editable(&self) -> bool {
+        self.get_direct_url()
+            .is_some_and(|direct_url| direct_url.is_editable())
+    }
+
+    pub fn get_record(&self) -> &Vec<PipRecordEntry> {
+        self.record
+            .get_or_init(|| Self::read_record(&self.dist_info_path).unwrap_or_default())
+    }
+
+    /// Returns the paths of the installed files, relative to site-packages.
+    pub fn get_files(&self) -> Vec<&str> {
+        self.get_record()
+            .iter()
+            .map(|entry| entry.get_path())
+            .collect()
+    }
+
+    /// Returns the total on-disk size in bytes of the files listed in RECORD.
+    pub fn get_size(&self) -> u64 {
+        *self
+            .size
+            .get_or_init(|| Self::get_record_size(&self.dist_info_path, self.get_record()))
+    }
+
+    pub fn get_entry_points(&self) -> &Vec<PipEntryPoint> {
+        self.entry_points
+            .get_or_init(|| Self::read_entry_points(&self.dist_info_path).unwrap_or_default())
+    }
+
+    /// Returns the names of the console and GUI scripts the distribution installs.
+    pub fn get_scripts(&self) -> Vec<&str> {
+        self.get_entry_points()
+            .iter()
+            .filter(|entry_point| entry_point.is_script())
+            .map(|entry_point| entry_point.get_name())
+            .collect()
+    }
+}
+
+impl PipDistInfo {
+    fn read_installer(dist_info_path: &WPath) -> Option<String> {
+        let installer_path: WPath = dist_info_path.join("INSTALLER");
+        let installer: String = fs::read_to_string(installer_path).ok()?;
+        let installer: &str = installer.trim();
+        if installer.is_empty() {
+            return None;
+        }
+        Some(installer.to_string())
+    }
+
+    fn read_direct_url(dist_info_path: &WPath) -> Option<PipDirectUrl> {
+        let direct_url_path: WPath = dist_info_path.join("direct_url.json");
+        let direct_url: String = fs::read_to_string(direct_url_path).ok()?;
+        PipDirectUrl::from_string(&direct_url)
+    }
+
+    fn read_record(dist_info_path: &WPath) -> Result<Vec<PipRecordEntry>, io::Error> {
+        let record_path: WPath = dist_info_path.join("RECORD");
+        let file: File = File::open(record_path)?;
+        let reader: io::BufReader<File> = io::BufReader::new(file);
+
+        let mut record: Vec<PipRecordEntry> = Vec::new();
+        for line in reader.lines() {
+            let line: String = line?;
+            if let Some(entry) = PipRecordEntry::from_string(line.trim_end()) {
+                record.push(entry);
+            }
+        }
+        Ok(record)
+    }
+
+    fn read_entry_points(dist_info_path: &WPath) -> Result<Vec<PipEntryPoint>, io::Error> {
+        let entry_points_path: WPath = dist_info_path.join("entry_points.txt");
+        let file: File = File::open(entry_points_path)?;
+        let reader: io::BufReader<File> = io::BufReader::new(file);
+
+        let mut entry_points: Vec<PipEntryPoint> = Vec::new();
+        let mut group: String = String::new();
+        for line in reader.lines() {
+            let line: String = line?;
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(section) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                group = section.trim().to_string();
+            } else if let Some((name, object_reference)) = line.split_once('=') {
+                let entry_point: PipEntryPoint =
+                    PipEntryPoint::new(&group, name.trim(), object_reference.trim());
+                entry_points.push(entry_point);
+            }
+        }
+        Ok(entry_points)
+    }
+
+    /// Sums the sizes recorded in RECORD, reading the file size from disk for
+    /// entries such as RECORD itself or compiled files that leave it empty.
+    fn get_record_size(dist_info_path: &WPath, record: &[PipRecordEntry]) -> u64 {
+        let packages_dir: Option<&Path> = dist_info_path.get_path_buf().parent();
+        let mut size: u64 = 0;
+
+        for entry in record.iter() {
+            if let Some(entry_size) = entry.get_size() {
+                size += entry_size;
+            } else if let Some(packages_dir) = packages_dir {
+                let file_path: PathBuf = packages_dir.join(entry.get_path());
+                let metadata: Result<fs::Metadata, io::Error> = fs::metadata(file_path);
+                size += metadata.map(|metadata| metadata.len()).unwrap_or(0);
+            }
+        }
+        size
+    }
+}
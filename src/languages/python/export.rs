@@ -0,0 +1,363 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+use serde::Serialize;
+
+use super::dist_info::{PipDirectUrl, PipDistInfo};
+use super::pip::{PipPackage, PipPackageName};
+
+use crate::misc::path::WPath;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PipExportFormat {
+    Requirements,
+    Json,
+    Pyproject,
+    Constraints,
+}
+
+impl PipExportFormat {
+    pub fn from_string(string: &str) -> Option<Self> {
+        match string.trim().to_lowercase().as_str() {
+            "requirements" => Some(PipExportFormat::Requirements),
+            "json" => Some(PipExportFormat::Json),
+            "pyproject" => Some(PipExportFormat::Pyproject),
+            "constraints" => Some(PipExportFormat::Constraints),
+            _ => None,
+        }
+    }
+
+    pub fn get_string(&self) -> &str {
+        match self {
+            PipExportFormat::Requirements => "requirements",
+            PipExportFormat::Json => "json",
+            PipExportFormat::Pyproject => "pyproject",
+            PipExportFormat::Constraints => "constraints",
+        }
+    }
+
+    pub fn get_default_filename(&self, distill: bool) -> &str {
+        match (self, distill) {
+            (PipExportFormat::Requirements, false) => "packages.txt",
+            (PipExportFormat::Requirements, true) => "distilled_packages.txt",
+            (PipExportFormat::Json, false) => "packages.json",
+            (PipExportFormat::Json, true) => "distilled_packages.json",
+            (PipExportFormat::Pyproject, _) => "pyproject.toml",
+            (PipExportFormat::Constraints, _) => "constraints.txt",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ExportedDirectUrl {
+    url: String,
+    vcs: Option<String>,
+    commit_id: Option<String>,
+    hashes: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ExportedPackage {
+    name: String,
+    version: String,
+    extras: Vec<String>,
+    installer: Option<String>,
+    location: String,
+    editable: bool,
+    direct_url: Option<ExportedDirectUrl>,
+    size: u64,
+    scripts: Vec<String>,
+    hashes: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct ExportedEnvironment {
+    environment: String,
+    packages: Vec<ExportedPackage>,
+}
+
+pub struct PipExporter {
+    format: PipExportFormat,
+    environment_directory: WPath,
+}
+
+impl PipExporter {
+    pub fn new(format: PipExportFormat, environment_directory: &WPath) -> Self {
+        let environment_directory: WPath = environment_directory.clone();
+        PipExporter {
+            format,
+            environment_directory,
+        }
+    }
+
+    pub fn get_string(&self, packages: &[PipPackage]) -> Result<String, io::Error> {
+        match self.format {
+            PipExportFormat::Requirements => Ok(self.get_requirements_string(packages)),
+            PipExportFormat::Json => self.get_json_string(packages),
+            PipExportFormat::Pyproject => Ok(self.get_pyproject_string(packages)),
+            PipExportFormat::Constraints => Ok(self.get_constraints_string(packages)),
+        }
+    }
+
+    /// Writes the export to `file_path`, creating missing parent directories.
+    pub fn export(&self, packages: &[PipPackage], file_path: &WPath) -> Result<(), io::Error> {
+        let string: String = self.get_string(packages)?;
+        if let Some(parent) = file_path.get_path_buf().parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        fs::write(file_path, string)
+    }
+}
+
+impl PipExporter {
+    fn get_requirements_string(&self, packages: &[PipPackage]) -> String {
+        // A single `--hash` puts pip in hash-checking mode for the whole file, so hashes
+        // are only written as options when every package has one to offer.
+        let use_hashes: bool = !packages.is_empty()
+            && packages
+                .iter()
+                .all(|package| !self.get_archive_hashes(package).is_empty());
+
+        let mut lines: Vec<String> = vec![self.get_header_comment()];
+        for package in packages.iter() {
+            let direct_url: Option<&PipDirectUrl> = package.get_dist_info().get_direct_url();
+            let mut line: String = match direct_url {
+                Some(direct_url) if direct_url.is_editable() => {
+                    self.get_editable_string(package, direct_url)
+                }
+                Some(direct_url) => self.get_direct_url_string(package, direct_url),
+                None => package.get_requirement_string(),
+            };
+            if use_hashes {
+                for archive_hash in self.get_archive_hashes(package).iter() {
+                    line.push_str(&format!(" --hash={}", archive_hash));
+                }
+            }
+            let details: String = self.get_details_string(package, !use_hashes);
+            line.push_str(&format!("  # {}", details));
+            lines.push(line);
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn get_constraints_string(&self, packages: &[PipPackage]) -> String {
+        let mut lines: Vec<String> = vec![self.get_header_comment()];
+        for package in packages.iter() {
+            let name: &str = package.get_name().get_raw_string();
+            let direct_url: Option<&PipDirectUrl> = package.get_dist_info().get_direct_url();
+            let details: String = self.get_details_string(package, true);
+            // Constraints cannot carry extras, editable installs, links or hashes.
+            let line: String = match direct_url {
+                Some(direct_url) if direct_url.is_editable() => {
+                    let url: &str = direct_url.get_url();
+                    format!("# {}: installed from {}, {}", name, url, details)
+                }
+                Some(direct_url) => {
+                    let url: String = direct_url.get_requirement_url();
+                    format!("# {}: installed from {}, {}", name, url, details)
+                }
+                None => {
+                    let version: String = package.get_version().get_string();
+                    format!("{}=={}  # {}", name, version, details)
+                }
+            };
+            lines.push(line);
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn get_json_string(&self, packages: &[PipPackage]) -> Result<String, io::Error> {
+        let packages: Vec<ExportedPackage> = packages
+            .iter()
+            .map(|package| self.get_exported_package(package))
+            .collect();
+        let environment: ExportedEnvironment = ExportedEnvironment {
+            environment: self.get_environment_string(),
+            packages,
+        };
+
+        let json: Result<String, serde_json::Error> = serde_json::to_string_pretty(&environment);
+        json.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    fn get_pyproject_string(&self, packages: &[PipPackage]) -> String {
+        let environment_name: String = self
+            .environment_directory
+            .get_path_buf()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "environment".to_string());
+        let project_name: PipPackageName = PipPackageName::new(&environment_name);
+
+        let mut lines: Vec<String> = vec![
+            self.get_header_comment(),
+            "[project]".to_string(),
+            format!("name = {}", self.get_toml_string(project_name.get_string())),
+            "version = \"0.0.0\"".to_string(),
+            "dependencies = [".to_string(),
+        ];
+        for package in packages.iter() {
+            let direct_url: Option<&PipDirectUrl> = package.get_dist_info().get_direct_url();
+            let dependency: String = match direct_url {
+                Some(direct_url) => self.get_direct_url_string(package, direct_url),
+                None => package.get_requirement_string(),
+            };
+            lines.push(format!("    {},", self.get_toml_string(&dependency)));
+        }
+        lines.push("]".to_string());
+
+        for package in packages.iter() {
+            let dist_info: &PipDistInfo = package.get_dist_info();
+            let location: String = self.get_location_string(package);
+
+            lines.push(String::new());
+            lines.push(format!(
+                "[tool.arranger.packages.{}]",
+                package.get_name().get_string()
+            ));
+            if let Some(installer) = dist_info.get_installer() {
+                lines.push(format!("installer = {}", self.get_toml_string(installer)));
+            }
+            lines.push(format!("location = {}", self.get_toml_string(&location)));
+            lines.push(format!("editable = {}", dist_info.is_editable()));
+            if let Some(direct_url) = dist_info.get_direct_url() {
+                let url: String = direct_url.get_requirement_url();
+                lines.push(format!("url = {}", self.get_toml_string(&url)));
+            }
+            let archive_hashes: Vec<String> = self
+                .get_archive_hashes(package)
+                .iter()
+                .map(|archive_hash| self.get_toml_string(archive_hash))
+                .collect();
+            if !archive_hashes.is_empty() {
+                lines.push(format!("hashes = [{}]", archive_hashes.join(", ")));
+            }
+
+            let record_hashes: BTreeMap<String, String> = self.get_record_hashes(package);
+            if !record_hashes.is_empty() {
+                lines.push(String::new());
+                lines.push(format!(
+                    "[tool.arranger.packages.{}.record]",
+                    package.get_name().get_string()
+                ));
+                for (path, hash) in record_hashes.iter() {
+                    let path: String = self.get_toml_string(path);
+                    lines.push(format!("{} = {}", path, self.get_toml_string(hash)));
+                }
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn get_exported_package(&self, package: &PipPackage) -> ExportedPackage {
+        let dist_info: &PipDistInfo = package.get_dist_info();
+        let direct_url: Option<ExportedDirectUrl> =
+            dist_info
+                .get_direct_url()
+                .map(|direct_url| ExportedDirectUrl {
+                    url: direct_url.get_url().to_string(),
+                    vcs: direct_url.get_vcs().map(|vcs| vcs.to_string()),
+                    commit_id: direct_url.get_commit_id().map(|id| id.to_string()),
+                    hashes: direct_url.get_archive_hashes().clone(),
+                });
+        let hashes: BTreeMap<String, String> = self.get_record_hashes(package);
+
+        ExportedPackage {
+            name: package.get_name().get_raw_string().to_string(),
+            version: package.get_version().get_string(),
+            extras: package.get_extras().clone(),
+            installer: dist_info
+                .get_installer()
+                .map(|installer| installer.to_string()),
+            location: self.get_location_string(package),
+            editable: dist_info.is_editable(),
+            direct_url,
+            size: dist_info.get_size(),
+            scripts: dist_info
+                .get_scripts()
+                .iter()
+                .map(|script| script.to_string())
+                .collect(),
+            hashes,
+        }
+    }
+
+    fn get_editable_string(&self, package: &PipPackage, direct_url: &PipDirectUrl) -> String {
+        let url: String = direct_url.get_requirement_url();
+        if direct_url.get_vcs().is_some() {
+            let name: &str = package.get_name().get_raw_string();
+            return format!("-e {}#egg={}", url, name);
+        }
+        format!("-e {}", url)
+    }
+
+    fn get_direct_url_string(&self, package: &PipPackage, direct_url: &PipDirectUrl) -> String {
+        let name: &str = package.get_name().get_raw_string();
+        let extras: &Vec<String> = package.get_extras();
+        let url: String = direct_url.get_requirement_url();
+        if extras.is_empty() {
+            return format!("{} @ {}", name, url);
+        }
+        format!("{}[{}] @ {}", name, extras.join(","), url)
+    }
+
+    /// Returns the installer, location and archive hashes as a `key: value` list for
+    /// the text formats, which have no other place to record them.
+    fn get_details_string(&self, package: &PipPackage, include_hashes: bool) -> String {
+        let dist_info: &PipDistInfo = package.get_dist_info();
+        let installer: &str = dist_info.get_installer().unwrap_or("unknown");
+        let location: String = self.get_location_string(package);
+
+        let mut details: Vec<String> = vec![
+            format!("installer: {}", installer),
+            format!("location: {}", location),
+        ];
+        if dist_info.is_editable() {
+            details.push("editable".to_string());
+        }
+        let archive_hashes: Vec<String> = self.get_archive_hashes(package);
+        if include_hashes && !archive_hashes.is_empty() {
+            details.push(format!("hashes: {}", archive_hashes.join(" ")));
+        }
+        details.join(", ")
+    }
+
+    fn get_archive_hashes(&self, package: &PipPackage) -> Vec<String> {
+        let direct_url: Option<&PipDirectUrl> = package.get_dist_info().get_direct_url();
+        direct_url
+            .map(|direct_url| direct_url.get_archive_hashes().clone())
+            .unwrap_or_default()
+    }
+
+    fn get_record_hashes(&self, package: &PipPackage) -> BTreeMap<String, String> {
+        let mut hashes: BTreeMap<String, String> = BTreeMap::new();
+        for entry in package.get_dist_info().get_record().iter() {
+            if let Some(hash) = entry.get_hash() {
+                hashes.insert(entry.get_path().to_string(), hash.to_string());
+            }
+        }
+        hashes
+    }
+
+    fn get_location_string(&self, package: &PipPackage) -> String {
+        // Editable sources may not exist on this machine, so avoid canonicalizing.
+        let location: WPath = package.get_location();
+        location.get_path_buf().display().to_string()
+    }
+
+    fn get_header_comment(&self) -> String {
+        format!("# Environment: {}", self.get_environment_string())
+    }
+
+    fn get_environment_string(&self) -> String {
+        format!("{:?}", self.environment_directory)
+    }
+
+    fn get_toml_string(&self, string: &str) -> String {
+        // TOML basic strings share JSON's escape sequences.
+        serde_json::to_string(string).unwrap_or_else(|_| format!("\"{}\"", string))
+    }
+}
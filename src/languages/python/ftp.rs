@@ -1,11 +1,15 @@
 use core::fmt::Debug;
 use std::collections::HashSet;
 use std::error::Error;
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
 
 use regex::Regex;
 
-use crate::misc::https::HTTPS;
+use crate::misc::file_url::FileUrl;
+use crate::misc::https::{HTTPSConfig, HTTPS};
+use crate::misc::settings::Settings;
 use crate::misc::version::{PreRelease, SemanticVersion};
 use crate::terminal::Terminal;
 use crate::terminal::YellowANSI;
@@ -33,15 +37,21 @@ impl LinkType {
 pub struct FileStructure {
     url: String,
     structure: HashSet<LinkType>,
+    https_config: HTTPSConfig,
 }
 
 impl FileStructure {
-    pub async fn new(url: &str) -> Option<Self> {
+    pub async fn new(url: &str, https_config: &HTTPSConfig) -> Option<Self> {
         let structure: Result<HashSet<LinkType>, Box<dyn Error>> =
-            Self::build_file_structure(url).await;
+            Self::build_file_structure(url, https_config).await;
         if let Ok(structure) = structure {
             let url: String = url.to_string();
-            let file_structure: FileStructure = FileStructure { url, structure };
+            let https_config: HTTPSConfig = https_config.clone();
+            let file_structure: FileStructure = FileStructure {
+                url,
+                structure,
+                https_config,
+            };
             return Some(file_structure);
         }
         None
@@ -54,7 +64,7 @@ impl FileStructure {
                 LinkType::Directory(dir) => {
                     let new_url: String = format!("{}{}", self.url, dir);
                     let structure: Result<HashSet<LinkType>, Box<dyn std::error::Error>> =
-                        Self::build_file_structure(&new_url).await;
+                        Self::build_file_structure(&new_url, &self.https_config).await;
 
                     if let Ok(structure) = structure {
                         self.url = new_url;
@@ -103,9 +113,18 @@ impl FileStructure {
         }
     }
 
-    async fn build_file_structure(url: &str) -> Result<HashSet<LinkType>, Box<dyn Error>> {
-        let https: HTTPS = HTTPS::new();
-        let body: String = https.get_response_body(url).await?;
+    async fn build_file_structure(
+        url: &str,
+        https_config: &HTTPSConfig,
+    ) -> Result<HashSet<LinkType>, Box<dyn Error>> {
+        if let Some(path) = FileUrl::get_local_path(url) {
+            return Self::build_local_file_structure(&path);
+        }
+
+        let https: HTTPS = HTTPS::from_config(https_config.clone());
+        let body: String = https
+            .get_typed_response_body(url, Some("text/html"))
+            .await?;
 
         let mut links: HashSet<LinkType> = HashSet::new();
         let regex: Regex = Regex::new(r#"<a href="(.*?)""#).unwrap();
@@ -125,18 +144,139 @@ impl FileStructure {
         }
         Ok(links)
     }
+
+    /// Lists a local mirror the way an HTTP index page would, with `/` after directories.
+    fn build_local_file_structure(path: &Path) -> Result<HashSet<LinkType>, Box<dyn Error>> {
+        let mut links: HashSet<LinkType> = HashSet::new();
+        for entry in fs::read_dir(path)? {
+            let entry: fs::DirEntry = entry?;
+            let name: String = entry.file_name().to_string_lossy().to_string();
+            let link: String = if entry.path().is_dir() {
+                format!("{}/", name)
+            } else {
+                name
+            };
+            if let Some(link_type) = LinkType::new(&link) {
+                links.insert(link_type);
+            }
+        }
+        Ok(links)
+    }
 }
 
 pub struct PythonFTPRetriever {
     ftp_url: String,
+    include_pre_release: bool,
+    extensions: Vec<String>,
+    https_config: HTTPSConfig,
 }
 
 impl PythonFTPRetriever {
     pub fn new() -> Self {
         let ftp_url = "https://www.python.org/ftp/python/".to_string();
-        PythonFTPRetriever { ftp_url }
+        PythonFTPRetriever {
+            ftp_url,
+            include_pre_release: false,
+            extensions: Self::get_default_extensions(),
+            https_config: HTTPSConfig::new(),
+        }
     }
 
+    /// Uses a mirror with the same layout as python.org. Local directories may be given
+    /// as plain paths or `file://` URLs.
+    pub fn from_url(url: &str) -> Self {
+        let url: &str = url.trim();
+        let mut ftp_url: String = if url.contains("://") {
+            url.to_string()
+        } else {
+            let path: PathBuf = fs::canonicalize(url).unwrap_or_else(|_| PathBuf::from(url));
+            let path: String = path.to_string_lossy().to_string();
+            let path: String = path.trim_start_matches(r"\\?\").replace('\\', "/");
+            FileUrl::get_file_url(&path)
+        };
+        if !ftp_url.ends_with('/') {
+            ftp_url.push('/');
+        }
+        PythonFTPRetriever {
+            ftp_url,
+            include_pre_release: false,
+            extensions: Self::get_default_extensions(),
+            https_config: HTTPSConfig::new(),
+        }
+    }
+
+    /// Picks `mirror`, then the `python_mirror` setting, then python.org.
+    pub fn from_mirror(mirror: Option<&str>) -> Self {
+        let settings: Settings = Settings::new();
+        match mirror.or(settings.get_python_mirror()) {
+            Some(mirror) => Self::from_url(mirror),
+            None => Self::new(),
+        }
+    }
+
+    pub fn get_url(&self) -> &str {
+        &self.ftp_url
+    }
+
+    /// Includes pre-release installers such as `3.13.0rc1` when looking up setup files.
+    pub fn set_include_pre_release(&mut self, include_pre_release: bool) {
+        self.include_pre_release = include_pre_release;
+    }
+
+    /// Sets the timeouts, retries and proxies used to list the mirror.
+    pub fn set_https_config(&mut self, https_config: HTTPSConfig) {
+        self.https_config = https_config;
+    }
+
+    /// Only accepts setup files with this extension, such as `tar.xz` or `exe`.
+    pub fn set_extension(&mut self, extension: &str) {
+        self.extensions = vec![extension.trim_start_matches('.').to_string()];
+    }
+
+    /// Returns every version directory in the root index, newest first.
+    pub async fn get_versions(&self) -> Vec<SemanticVersion> {
+        self.get_release_directories()
+            .await
+            .into_iter()
+            .map(|(version, _)| version)
+            .collect()
+    }
+
+    /// Returns every version with its directory in the root index, newest first.
+    pub async fn get_release_directories(&self) -> Vec<(SemanticVersion, String)> {
+        let file_structure: Option<FileStructure> =
+            FileStructure::new(&self.ftp_url, &self.https_config).await;
+        match file_structure {
+            Some(file_structure) => self.get_version_directories(&file_structure),
+            None => Vec::new(),
+        }
+    }
+
+    /// Parses the Python artifacts in a version directory, skipping pre-releases unless
+    /// they are included. Returns `None` when the directory cannot be listed.
+    pub async fn get_release_files(&self, directory: &str) -> Option<Vec<PythonFilename>> {
+        let url: String = format!("{}{}", self.ftp_url, directory);
+        let file_structure: FileStructure = FileStructure::new(&url, &self.https_config).await?;
+
+        let mut release_files: Vec<PythonFilename> = Vec::new();
+        for link in file_structure.get_structure() {
+            if let LinkType::File(file) = link {
+                let filename: Option<PythonFilename> = PythonFilename::new(&file);
+                if let Some(filename) = filename {
+                    let is_pre_release: bool = filename.version.get_pre_release().is_some();
+                    if is_pre_release && !self.include_pre_release {
+                        continue;
+                    }
+                    release_files.push(filename);
+                }
+            }
+        }
+        Some(release_files)
+    }
+
+    /// Resolves the highest patch of `version`'s major.minor that has a matching setup
+    /// file. The root index is fetched once, then only candidate directories are listed,
+    /// newest first, since late security patches may publish source releases only.
     pub async fn get_setup_file_latest_patch(
         &self,
         version: &mut SemanticVersion,
@@ -145,34 +285,36 @@ impl PythonFTPRetriever {
         package_type: &str,
     ) -> Option<String> {
         let terminal: Terminal = Terminal::new();
-        let mut setup_file: Option<String> = None;
-        let mut counter: usize = 0;
-        let limit: usize = 50;
-        version.set_patch(0);
-
-        while counter <= limit {
-            counter += 1;
-            let file: Option<String> = self
-                .get_setup_file(&version, arch, platform, package_type)
-                .await;
-
-            if let Some(_) = file {
-                setup_file = file;
-            } else if version.get_patch() == 0 {
-            } else {
-                let patch: usize = version.get_patch();
-                version.set_patch(patch - 1);
-                break;
-            }
+        let file_structure: FileStructure =
+            FileStructure::new(&self.ftp_url, &self.https_config).await?;
+        let (major, minor): (usize, usize) = version.get_2p_version();
+
+        let directories: Vec<(SemanticVersion, String)> = self
+            .get_version_directories(&file_structure)
+            .into_iter()
+            .filter(|(candidate, _)| candidate.get_2p_version() == (major, minor))
+            .collect();
 
-            let parts: [&str; 2] = ["\rVersion: ", &version.get_string()];
+        for (candidate, directory) in directories {
+            let parts: [&str; 2] = ["\rVersion: ", &candidate.get_string()];
             terminal.write_parameter(&parts, &YellowANSI);
 
-            let patch: usize = version.get_patch();
-            version.set_patch(patch + 1);
+            let url: String = format!("{}{}", self.ftp_url, directory);
+            let candidate_structure: Option<FileStructure> =
+                FileStructure::new(&url, &self.https_config).await;
+            let setup_file: Option<String> = candidate_structure.and_then(|structure| {
+                let links: HashSet<LinkType> = structure.get_structure();
+                self.find_setup_file(&links, arch, platform, package_type)
+            });
+
+            if let Some(setup_file) = setup_file {
+                println!();
+                version.set_patch(candidate.get_patch());
+                return Some(format!("{}{}", url, setup_file));
+            }
         }
         println!();
-        setup_file
+        None
     }
 
     pub async fn get_setup_file(
@@ -183,7 +325,8 @@ impl PythonFTPRetriever {
         package_type: &str,
     ) -> Option<String> {
         let version_3p_directory: String = self.get_3p_version_directory(version);
-        let file_structure: Option<FileStructure> = FileStructure::new(&self.ftp_url).await;
+        let file_structure: Option<FileStructure> =
+            FileStructure::new(&self.ftp_url, &self.https_config).await;
 
         if let Some(mut file_structure) = file_structure {
             let mut result: bool = file_structure.access_directory(&version_3p_directory).await;
@@ -206,9 +349,34 @@ impl PythonFTPRetriever {
         None
     }
 
+    /// Returns the URLs of the digest and signature files published next to an artifact,
+    /// with digest files ordered first.
+    pub async fn get_sidecar_urls(&self, url: &str) -> Vec<String> {
+        let (directory_url, file): (&str, &str) = match url.rsplit_once('/') {
+            Some((directory_url, file)) => (directory_url, file),
+            None => return Vec::new(),
+        };
+        let directory_url: String = format!("{}/", directory_url);
+        let file_structure: Option<FileStructure> =
+            FileStructure::new(&directory_url, &self.https_config).await;
+
+        let mut sidecar_urls: Vec<String> = Vec::new();
+        if let Some(file_structure) = file_structure {
+            let structure: HashSet<LinkType> = file_structure.get_structure();
+            for extension in ["sha256", "sigstore", "sig", "asc"] {
+                let sidecar: LinkType = LinkType::File(format!("{}.{}", file, extension));
+                if structure.contains(&sidecar) {
+                    sidecar_urls.push(format!("{}{}.{}", directory_url, file, extension));
+                }
+            }
+        }
+        sidecar_urls
+    }
+
     pub async fn list_file_structure(&self, version: &SemanticVersion) {
         let version_3p_directory: String = self.get_3p_version_directory(version);
-        let file_structure: Option<FileStructure> = FileStructure::new(&self.ftp_url).await;
+        let file_structure: Option<FileStructure> =
+            FileStructure::new(&self.ftp_url, &self.https_config).await;
 
         if let Some(mut file_structure) = file_structure {
             let mut result: bool = file_structure.access_directory(&version_3p_directory).await;
@@ -243,6 +411,53 @@ impl PythonFTPRetriever {
         }
     }
 
+    /// Parses `X.Y.Z/` and `X.Y/` directory names, keeping one directory per version
+    /// and sorting newest first.
+    fn get_version_directories(
+        &self,
+        file_structure: &FileStructure,
+    ) -> Vec<(SemanticVersion, String)> {
+        let mut directories: Vec<(SemanticVersion, String)> = Vec::new();
+        for link in file_structure.get_structure() {
+            if let LinkType::Directory(directory) = link {
+                let name: &str = directory.trim_end_matches('/');
+                let is_numeric: bool = name
+                    .split('.')
+                    .all(|part| !part.is_empty() && part.chars().all(|char| char.is_ascii_digit()));
+                if !is_numeric {
+                    continue;
+                }
+                if let Some(version) = SemanticVersion::from_string(name) {
+                    directories.push((version, directory));
+                }
+            }
+        }
+
+        // Prefer `X.Y.0/` over the older two-part `X.Y/` layout for the same version.
+        directories.sort_by(|(a, a_dir), (b, b_dir)| {
+            b.get_3p_version()
+                .cmp(&a.get_3p_version())
+                .then(b_dir.len().cmp(&a_dir.len()))
+        });
+        directories.dedup_by(|(a, _), (b, _)| a.get_3p_version() == b.get_3p_version());
+        directories
+    }
+
+    /// Installer and archive extensions in order of preference. Linux prefers the
+    /// `tar.xz` source archive, other platforms keep the `tgz`.
+    fn get_default_extensions() -> Vec<String> {
+        let source_extensions: [&str; 3] = if cfg!(target_os = "linux") {
+            ["tar.xz", "tgz", "tar.bz2"]
+        } else {
+            ["tgz", "tar.xz", "tar.bz2"]
+        };
+        ["exe", "msi", "pkg", "dmg", "zip", "nupkg", "tar.gz"]
+            .iter()
+            .chain(source_extensions.iter())
+            .map(|extension| extension.to_string())
+            .collect()
+    }
+
     fn get_3p_version_directory(&self, version: &SemanticVersion) -> String {
         let (major, minor, patch): (usize, usize, usize) = version.get_3p_version();
         let version_directory: String = format!("{}.{}.{}/", major, minor, patch);
@@ -262,8 +477,8 @@ impl PythonFTPRetriever {
         platform: &str,
         package_type: &str,
     ) -> Option<String> {
-        let mut setup_file: Option<String> = None;
-        let mut python_filename: Option<PythonFilename> = None;
+        let extensions: Vec<&str> = self.extensions.iter().map(String::as_str).collect();
+        let mut setup_file: Option<(String, PythonFilename)> = None;
 
         for link in structure {
             match link {
@@ -275,39 +490,52 @@ impl PythonFTPRetriever {
                             arch,
                             package_type,
                             platform,
-                            &["exe", "msi", "pkg", "dmg", "tgz"],
+                            &extensions,
                         );
-                        if requirement {
-                            if let Some(_python_filename) = &python_filename {
-                                let version: &SemanticVersion = &filename.version;
-                                let prev_version: &SemanticVersion = &_python_filename.version;
-
-                                let pre_release: &Option<PreRelease> = version.get_pre_release();
-                                let prev_pre_release: &Option<PreRelease> =
-                                    prev_version.get_pre_release();
-
-                                if let Some(pre_release) = pre_release {
-                                    if let Some(prev_pre_release) = prev_pre_release {
-                                        if pre_release < prev_pre_release {
-                                            continue;
-                                        }
-                                    } else {
-                                        continue;
-                                    }
-                                }
-                                setup_file = Some(file.to_string());
-                                python_filename = Some(filename);
-                            } else {
-                                setup_file = Some(file.to_string());
-                                python_filename = Some(filename);
-                            }
+                        let is_pre_release: bool = filename.version.get_pre_release().is_some();
+                        if !requirement || (is_pre_release && !self.include_pre_release) {
+                            continue;
+                        }
+
+                        let is_preferred: bool = match &setup_file {
+                            Some((_, current)) => self.is_preferred(&filename, current),
+                            None => true,
+                        };
+                        if is_preferred {
+                            setup_file = Some((file.to_string(), filename));
                         }
                     }
                 }
                 LinkType::Directory(_) => {}
             }
         }
-        setup_file
+        setup_file.map(|(file, _)| file)
+    }
+
+    /// Prefers final releases over pre-releases, then the newest pre-release, then the
+    /// extension listed first.
+    fn is_preferred(&self, candidate: &PythonFilename, current: &PythonFilename) -> bool {
+        let pre_release: &Option<PreRelease> = candidate.version.get_pre_release();
+        let current_pre_release: &Option<PreRelease> = current.version.get_pre_release();
+
+        match (pre_release, current_pre_release) {
+            (None, Some(_)) => return true,
+            (Some(_), None) => return false,
+            (Some(pre_release), Some(current_pre_release))
+                if pre_release != current_pre_release =>
+            {
+                return pre_release > current_pre_release;
+            }
+            _ => {}
+        }
+        self.get_extension_rank(candidate) < self.get_extension_rank(current)
+    }
+
+    fn get_extension_rank(&self, filename: &PythonFilename) -> usize {
+        self.extensions
+            .iter()
+            .position(|extension| extension == &filename.extension)
+            .unwrap_or(usize::MAX)
     }
 }
 
@@ -323,19 +551,21 @@ pub struct PythonFilename {
 
 impl PythonFilename {
     pub fn new(filename: &str) -> Option<Self> {
-        let parts: Vec<&str> = filename.rsplitn(2, '.').collect();
+        let parts: Option<(&str, &str)> = Self::split_extension(filename);
 
-        if parts.len() == 2 {
-            let (main_part, extension) = (parts[1], parts[0]);
-
-            if Self::is_valid_extension(extension) {
+        if let Some((main_part, extension)) = parts {
+            if Self::is_valid_extension(extension) && !Self::is_documentation(main_part) {
                 let (name, version, architecture, package_type, platform): (
                     Option<String>,
                     Option<SemanticVersion>,
                     String,
                     String,
                     Option<String>,
-                ) = Self::get_components(main_part, extension);
+                ) = if extension == "nupkg" {
+                    Self::get_nuget_components(main_part)
+                } else {
+                    Self::get_components(main_part, extension)
+                };
 
                 if let (Some(name), Some(version), Some(platform)) = (name, version, platform) {
                     let extension: String = extension.to_string();
@@ -353,6 +583,26 @@ impl PythonFilename {
         None
     }
 
+    pub fn get_version(&self) -> &SemanticVersion {
+        &self.version
+    }
+
+    pub fn get_architecture(&self) -> &str {
+        &self.architecture
+    }
+
+    pub fn get_package_type(&self) -> &str {
+        &self.package_type
+    }
+
+    pub fn get_platform(&self) -> &str {
+        &self.platform
+    }
+
+    pub fn get_extension(&self) -> &str {
+        &self.extension
+    }
+
     pub fn match_requirements(
         &self,
         name: &str,
@@ -372,6 +622,28 @@ impl PythonFilename {
         false
     }
 
+    /// Splits off the extension, keeping compressed tarballs such as `tar.xz` whole.
+    fn split_extension(filename: &str) -> Option<(&str, &str)> {
+        for extension in ["tar.xz", "tar.gz", "tar.bz2"] {
+            let main_part: Option<&str> = filename.strip_suffix(&format!(".{}", extension));
+            if let Some(main_part) = main_part {
+                return Some((main_part, extension));
+            }
+        }
+        filename.rsplit_once('.')
+    }
+
+    fn is_documentation(main_part: &str) -> bool {
+        main_part
+            .to_lowercase()
+            .split('-')
+            .any(|segment| segment == "docs")
+    }
+
+    fn is_source(extension: &str) -> bool {
+        ["tgz", "tar.xz", "tar.bz2"].contains(&extension)
+    }
+
     fn is_name(segment: &str) -> bool {
         if segment == "python" {
             return true;
@@ -389,12 +661,14 @@ impl PythonFilename {
             "amd64" => return true,
             "arm64" => return true,
             "ia64" => return true,
+            "aarch64" => return true,
+            "x86_64" => return true,
             _ => return false,
         }
     }
 
     fn is_package_type(segment: &str, extension: &str) -> Option<String> {
-        if extension == "tgz" {
+        if Self::is_source(extension) {
             return Some("source".to_string());
         };
 
@@ -407,7 +681,8 @@ impl PythonFilename {
     }
 
     fn is_platform(segment: &str, extension: &str) -> Option<String> {
-        if ["exe", "msi"].contains(&extension) || segment == "win32" {
+        let is_embed_zip: bool = segment == "embed" && extension == "zip";
+        if ["exe", "msi", "nupkg"].contains(&extension) || segment == "win32" || is_embed_zip {
             return Some("windows".to_string());
         }
 
@@ -415,7 +690,11 @@ impl PythonFilename {
             return Some("macos".to_string());
         }
 
-        if extension == "tgz" {
+        if ["android", "ios"].contains(&segment) {
+            return Some(segment.to_string());
+        }
+
+        if Self::is_source(extension) {
             return Some("any".to_string());
         }
         None
@@ -429,6 +708,10 @@ impl PythonFilename {
             "pkg" => true,
             "dmg" => true,
             "tgz" => true,
+            "tar.xz" => true,
+            "tar.gz" => true,
+            "tar.bz2" => true,
+            "nupkg" => true,
             _ => false,
         }
     }
@@ -482,7 +765,36 @@ impl PythonFilename {
         (name, version, architecture, package_type, platform)
     }
 
+    /// NuGet packages are named `<package>.<version>.nupkg`, where the package is
+    /// `python`, `pythonx86` or `pythonarm64`.
+    fn get_nuget_components(
+        main_part: &str,
+    ) -> (
+        Option<String>,
+        Option<SemanticVersion>,
+        String,
+        String,
+        Option<String>,
+    ) {
+        let (package, version): (&str, &str) = main_part.split_once('.').unwrap_or((main_part, ""));
+        let architecture: Option<&str> = match package.to_lowercase().as_str() {
+            "python" => Some("amd64"),
+            "pythonarm64" => Some("arm64"),
+            "pythonx86" => Some("n/a"),
+            _ => None,
+        };
+        let name: Option<String> = architecture.map(|_| "python".to_string());
+        let version: Option<SemanticVersion> = Self::is_version(&version.replace('-', ""));
+        let architecture: String = architecture.unwrap_or("n/a").to_string();
+        let platform: Option<String> = Some("windows".to_string());
+
+        (name, version, architecture, "nuget".to_string(), platform)
+    }
+
     fn process_split(split: &mut Vec<String>) {
+        if split.len() < 2 {
+            return;
+        }
         let second_part: &String = &split[1];
         let mut version_part: Vec<char> = Vec::new();
         let mut version_segments: usize = 0;
@@ -544,3 +856,146 @@ impl Debug for PythonFilename {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    /// Creates a local mirror under a directory name with a space, so every file URL
+    /// built from it needs percent-encoding. Entries ending in `/` are empty directories.
+    fn create_mirror(name: &str, entries: &[&str]) -> PathBuf {
+        let directory_name: String = format!("arranger ftp-{}-{}", process::id(), name);
+        let root: PathBuf = std::env::temp_dir().join(directory_name);
+        let _ = fs::remove_dir_all(&root);
+
+        for entry in entries {
+            let path: PathBuf = root.join(entry);
+            if entry.ends_with('/') {
+                fs::create_dir_all(&path).unwrap();
+            } else {
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, b"").unwrap();
+            }
+        }
+        root
+    }
+
+    fn get_structure(files: &[&str]) -> HashSet<LinkType> {
+        files
+            .iter()
+            .map(|file| LinkType::File(file.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn lists_release_directories_newest_first() {
+        let root: PathBuf = create_mirror(
+            "directories",
+            &[
+                "3.11.8/",
+                "3.12/Python-3.12.tgz",
+                "3.12.0/Python-3.12.0.tgz",
+                "3.12.1/",
+                "docs/",
+                "index.txt",
+            ],
+        );
+        let ftp_retriever: PythonFTPRetriever =
+            PythonFTPRetriever::from_url(&root.to_string_lossy());
+        let directories: Vec<(SemanticVersion, String)> =
+            ftp_retriever.get_release_directories().await;
+        fs::remove_dir_all(&root).unwrap();
+
+        let directories: Vec<((usize, usize, usize), &str)> = directories
+            .iter()
+            .map(|(version, directory)| (version.get_3p_version(), directory.as_str()))
+            .collect();
+        assert_eq!(
+            directories,
+            vec![
+                ((3, 12, 1), "3.12.1/"),
+                ((3, 12, 0), "3.12.0/"),
+                ((3, 11, 8), "3.11.8/"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_latest_patch_with_matching_file() {
+        let root: PathBuf = create_mirror(
+            "latest-patch",
+            &[
+                "3.12.0/Python-3.12.0.tar.xz",
+                "3.12.1/Python-3.12.1.tar.xz",
+                "3.12.2/python-3.12.2-amd64.exe",
+                "3.13.0/Python-3.13.0.tar.xz",
+            ],
+        );
+        let mut ftp_retriever: PythonFTPRetriever =
+            PythonFTPRetriever::from_url(&root.to_string_lossy());
+
+        let mut version: SemanticVersion = SemanticVersion::from_string("3.12.0").unwrap();
+        let installer: Option<String> = ftp_retriever
+            .get_setup_file_latest_patch(&mut version, "amd64", "windows", "standard")
+            .await;
+        let installer: String = installer.unwrap();
+        assert!(installer.ends_with("/3.12.2/python-3.12.2-amd64.exe"));
+        assert_eq!(version.get_3p_version(), (3, 12, 2));
+
+        // Only the source archives count once the extension is fixed.
+        ftp_retriever.set_extension("tar.xz");
+        let mut version: SemanticVersion = SemanticVersion::from_string("3.12.0").unwrap();
+        let source: Option<String> = ftp_retriever
+            .get_setup_file_latest_patch(&mut version, "n/a", "any", "source")
+            .await;
+        fs::remove_dir_all(&root).unwrap();
+
+        let source: String = source.unwrap();
+        assert!(source.ends_with("/3.12.1/Python-3.12.1.tar.xz"));
+        assert!(FileUrl::get_local_path(&source).is_some());
+        assert_eq!(version.get_3p_version(), (3, 12, 1));
+    }
+
+    #[test]
+    fn finds_preferred_source_archive() {
+        let structure: HashSet<LinkType> = get_structure(&[
+            "Python-3.12.0.tgz",
+            "Python-3.12.0.tar.xz",
+            "Python-3.12.0.tar.bz2",
+            "python-3.12.0-docs-html.tar.bz2",
+        ]);
+        let mut ftp_retriever: PythonFTPRetriever = PythonFTPRetriever::new();
+
+        let expected: &str = if cfg!(target_os = "linux") {
+            "Python-3.12.0.tar.xz"
+        } else {
+            "Python-3.12.0.tgz"
+        };
+        let setup_file: Option<String> =
+            ftp_retriever.find_setup_file(&structure, "n/a", "any", "source");
+        assert_eq!(setup_file.as_deref(), Some(expected));
+
+        ftp_retriever.set_extension(".tar.bz2");
+        let setup_file: Option<String> =
+            ftp_retriever.find_setup_file(&structure, "n/a", "any", "source");
+        assert_eq!(setup_file.as_deref(), Some("Python-3.12.0.tar.bz2"));
+    }
+
+    #[test]
+    fn finds_newest_pre_release_only_when_included() {
+        let structure: HashSet<LinkType> =
+            get_structure(&["Python-3.13.0b4.tgz", "Python-3.13.0rc1.tgz"]);
+        let mut ftp_retriever: PythonFTPRetriever = PythonFTPRetriever::new();
+
+        let setup_file: Option<String> =
+            ftp_retriever.find_setup_file(&structure, "n/a", "any", "source");
+        assert_eq!(setup_file, None);
+
+        ftp_retriever.set_include_pre_release(true);
+        let setup_file: Option<String> =
+            ftp_retriever.find_setup_file(&structure, "n/a", "any", "source");
+        assert_eq!(setup_file.as_deref(), Some("Python-3.13.0rc1.tgz"));
+    }
+}
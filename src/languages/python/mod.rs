@@ -1,4 +1,13 @@
+pub mod comparison;
+pub mod dependency;
+pub mod dist_info;
+pub mod export;
+pub mod pep440;
+pub mod pep508;
 pub mod pip;
 pub mod python;
 pub mod ftp;
 pub mod virtualenv;
+pub mod diagnosis;
+pub mod relocate;
+pub mod verify;
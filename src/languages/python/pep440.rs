@@ -0,0 +1,630 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PipPreRelease {
+    Alpha,
+    Beta,
+    ReleaseCandidate,
+}
+
+impl PipPreRelease {
+    fn from_string(string: &str) -> Option<Self> {
+        match string {
+            "a" | "alpha" => Some(PipPreRelease::Alpha),
+            "b" | "beta" => Some(PipPreRelease::Beta),
+            "rc" | "c" | "pre" | "preview" => Some(PipPreRelease::ReleaseCandidate),
+            _ => None,
+        }
+    }
+
+    fn get_string(&self) -> &str {
+        match self {
+            PipPreRelease::Alpha => "a",
+            PipPreRelease::Beta => "b",
+            PipPreRelease::ReleaseCandidate => "rc",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipLocalSegment {
+    Number(u64),
+    Text(String),
+}
+
+impl PartialOrd for PipLocalSegment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PipLocalSegment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Numeric segments always sort after alphanumeric ones.
+        match (self, other) {
+            (PipLocalSegment::Number(left), PipLocalSegment::Number(right)) => left.cmp(right),
+            (PipLocalSegment::Text(left), PipLocalSegment::Text(right)) => left.cmp(right),
+            (PipLocalSegment::Number(_), PipLocalSegment::Text(_)) => Ordering::Greater,
+            (PipLocalSegment::Text(_), PipLocalSegment::Number(_)) => Ordering::Less,
+        }
+    }
+}
+
+/// A PEP 440 version. Strings that do not follow PEP 440 are kept as legacy
+/// versions which sort before every valid version.
+#[derive(Debug, Clone)]
+pub struct PipVersion {
+    epoch: u64,
+    release: Vec<u64>,
+    pre: Option<(PipPreRelease, u64)>,
+    post: Option<u64>,
+    dev: Option<u64>,
+    local: Vec<PipLocalSegment>,
+    legacy: Option<String>,
+}
+
+impl PipVersion {
+    pub fn from_string(string: &str) -> Option<Self> {
+        let string: String = string.trim().to_lowercase();
+        let mut parser: Pep440Parser = Pep440Parser::new(&string);
+        parser.parse_version()
+    }
+
+    pub fn from_string_or_legacy(string: &str) -> Self {
+        let version: Option<PipVersion> = Self::from_string(string);
+        version.unwrap_or_else(|| PipVersion {
+            epoch: 0,
+            release: Vec::new(),
+            pre: None,
+            post: None,
+            dev: None,
+            local: Vec::new(),
+            legacy: Some(string.trim().to_string()),
+        })
+    }
+
+    pub fn get_release(&self) -> &Vec<u64> {
+        &self.release
+    }
+
+    pub fn is_legacy(&self) -> bool {
+        self.legacy.is_some()
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    pub fn is_postrelease(&self) -> bool {
+        self.post.is_some()
+    }
+
+    pub fn has_local(&self) -> bool {
+        !self.local.is_empty()
+    }
+
+    /// Returns the version with its local segment removed, e.g. `1.0+cpu` -> `1.0`.
+    pub fn get_public(&self) -> PipVersion {
+        let mut version: PipVersion = self.clone();
+        version.local.clear();
+        version
+    }
+
+    pub fn get_base_string(&self) -> String {
+        let release: Vec<String> = self.release.iter().map(|part| part.to_string()).collect();
+        let mut string: String = String::new();
+        if self.epoch != 0 {
+            string.push_str(&format!("{}!", self.epoch));
+        }
+        string.push_str(&release.join("."));
+        string
+    }
+
+    pub fn get_string(&self) -> String {
+        if let Some(legacy) = &self.legacy {
+            return legacy.clone();
+        }
+
+        let mut string: String = self.get_base_string();
+        if let Some((kind, number)) = &self.pre {
+            string.push_str(&format!("{}{}", kind.get_string(), number));
+        }
+        if let Some(post) = self.post {
+            string.push_str(&format!(".post{}", post));
+        }
+        if let Some(dev) = self.dev {
+            string.push_str(&format!(".dev{}", dev));
+        }
+        if !self.local.is_empty() {
+            let local: Vec<String> = self
+                .local
+                .iter()
+                .map(|segment| match segment {
+                    PipLocalSegment::Number(number) => number.to_string(),
+                    PipLocalSegment::Text(text) => text.clone(),
+                })
+                .collect();
+            string.push_str(&format!("+{}", local.join(".")));
+        }
+        string
+    }
+}
+
+impl PipVersion {
+    fn get_release_ordering(&self, other: &Self) -> Ordering {
+        let length: usize = self.release.len().max(other.release.len());
+        for idx in 0..length {
+            let left: u64 = self.release.get(idx).copied().unwrap_or(0);
+            let right: u64 = other.release.get(idx).copied().unwrap_or(0);
+            let ordering: Ordering = left.cmp(&right);
+            if ordering.is_ne() {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// Pre-release key where a bare dev release sorts before any pre-release
+    /// and a final release after all of them.
+    fn get_pre_key(&self) -> (u8, Option<(PipPreRelease, u64)>) {
+        match (&self.pre, &self.post, &self.dev) {
+            (None, None, Some(_)) => (0, None),
+            (Some(pre), _, _) => (1, Some(*pre)),
+            (None, _, _) => (2, None),
+        }
+    }
+
+    fn get_post_key(&self) -> (u8, u64) {
+        match self.post {
+            Some(post) => (1, post),
+            None => (0, 0),
+        }
+    }
+
+    fn get_dev_key(&self) -> (u8, u64) {
+        match self.dev {
+            Some(dev) => (0, dev),
+            None => (1, 0),
+        }
+    }
+}
+
+impl PartialEq for PipVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PipVersion {}
+
+impl PartialOrd for PipVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PipVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.legacy, &other.legacy) {
+            (Some(left), Some(right)) => return left.cmp(right),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => {}
+        }
+
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| self.get_release_ordering(other))
+            .then_with(|| self.get_pre_key().cmp(&other.get_pre_key()))
+            .then_with(|| self.get_post_key().cmp(&other.get_post_key()))
+            .then_with(|| self.get_dev_key().cmp(&other.get_dev_key()))
+            .then_with(|| self.local.cmp(&other.local))
+    }
+}
+
+impl fmt::Display for PipVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipOperator {
+    Compatible,
+    Equal,
+    NotEqual,
+    LessEqual,
+    GreaterEqual,
+    Less,
+    Greater,
+    Arbitrary,
+}
+
+impl PipOperator {
+    pub fn from_string(string: &str) -> Option<Self> {
+        match string {
+            "~=" => Some(PipOperator::Compatible),
+            "==" => Some(PipOperator::Equal),
+            "!=" => Some(PipOperator::NotEqual),
+            "<=" => Some(PipOperator::LessEqual),
+            ">=" => Some(PipOperator::GreaterEqual),
+            "<" => Some(PipOperator::Less),
+            ">" => Some(PipOperator::Greater),
+            "===" => Some(PipOperator::Arbitrary),
+            _ => None,
+        }
+    }
+
+    pub fn get_string(&self) -> &str {
+        match self {
+            PipOperator::Compatible => "~=",
+            PipOperator::Equal => "==",
+            PipOperator::NotEqual => "!=",
+            PipOperator::LessEqual => "<=",
+            PipOperator::GreaterEqual => ">=",
+            PipOperator::Less => "<",
+            PipOperator::Greater => ">",
+            PipOperator::Arbitrary => "===",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PipSpecifier {
+    operator: PipOperator,
+    version: String,
+}
+
+impl PipSpecifier {
+    pub fn new(operator: PipOperator, version: &str) -> Self {
+        let version: String = version.trim().to_string();
+        PipSpecifier { operator, version }
+    }
+
+    pub fn get_operator(&self) -> &PipOperator {
+        &self.operator
+    }
+
+    pub fn get_version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn get_string(&self) -> String {
+        format!("{}{}", self.operator.get_string(), self.version)
+    }
+
+    pub fn contains(&self, candidate: &PipVersion) -> bool {
+        if self.operator == PipOperator::Arbitrary {
+            return candidate.get_string().eq_ignore_ascii_case(&self.version);
+        }
+
+        if let Some(prefix) = self.version.strip_suffix(".*") {
+            let prefix: Option<PipVersion> = PipVersion::from_string(prefix);
+            let is_prefix: bool = prefix.is_some_and(|prefix| Self::is_prefix(&prefix, candidate));
+            return match self.operator {
+                PipOperator::Equal => is_prefix,
+                PipOperator::NotEqual => !is_prefix,
+                _ => false,
+            };
+        }
+
+        let version: Option<PipVersion> = PipVersion::from_string(&self.version);
+        let version: PipVersion = match version {
+            Some(version) => version,
+            None => return false,
+        };
+
+        // Local segments only take part in comparisons when the specifier has one.
+        let candidate: PipVersion = if version.has_local() {
+            candidate.clone()
+        } else {
+            candidate.get_public()
+        };
+
+        match self.operator {
+            PipOperator::Equal => candidate == version,
+            PipOperator::NotEqual => candidate != version,
+            PipOperator::LessEqual => candidate <= version,
+            PipOperator::GreaterEqual => candidate >= version,
+            PipOperator::Less => {
+                candidate < version
+                    && (version.is_prerelease()
+                        || !candidate.is_prerelease()
+                        || !Self::is_same_release(&candidate, &version))
+            }
+            PipOperator::Greater => {
+                candidate > version
+                    && (version.is_postrelease()
+                        || !candidate.is_postrelease()
+                        || !Self::is_same_release(&candidate, &version))
+            }
+            PipOperator::Compatible => {
+                let release: &Vec<u64> = version.get_release();
+                if release.len() < 2 {
+                    return false;
+                }
+                let mut prefix: PipVersion = version.clone();
+                prefix.release.truncate(release.len() - 1);
+                prefix.pre = None;
+                prefix.post = None;
+                prefix.dev = None;
+                candidate >= version && Self::is_prefix(&prefix, &candidate)
+            }
+            PipOperator::Arbitrary => false,
+        }
+    }
+}
+
+impl PipSpecifier {
+    fn is_prefix(prefix: &PipVersion, candidate: &PipVersion) -> bool {
+        if prefix.epoch != candidate.epoch || candidate.is_legacy() {
+            return false;
+        }
+        prefix.release.iter().enumerate().all(|(idx, part)| {
+            let candidate_part: u64 = candidate.release.get(idx).copied().unwrap_or(0);
+            *part == candidate_part
+        })
+    }
+
+    fn is_same_release(left: &PipVersion, right: &PipVersion) -> bool {
+        left.epoch == right.epoch && left.get_release_ordering(right).is_eq()
+    }
+}
+
+struct Pep440Parser<'a> {
+    string: &'a str,
+    position: usize,
+}
+
+impl<'a> Pep440Parser<'a> {
+    fn new(string: &'a str) -> Self {
+        Pep440Parser {
+            string,
+            position: 0,
+        }
+    }
+
+    fn parse_version(&mut self) -> Option<PipVersion> {
+        self.consume("v");
+
+        let mut epoch: u64 = 0;
+        let start: usize = self.position;
+        if let Some(number) = self.parse_number() {
+            if self.consume("!") {
+                epoch = number;
+            } else {
+                self.position = start;
+            }
+        }
+
+        let mut release: Vec<u64> = vec![self.parse_number()?];
+        loop {
+            let start: usize = self.position;
+            if self.consume(".") {
+                if let Some(number) = self.parse_number() {
+                    release.push(number);
+                    continue;
+                }
+            }
+            self.position = start;
+            break;
+        }
+
+        let pre: Option<(PipPreRelease, u64)> = self.parse_pre();
+        let post: Option<u64> = self.parse_post();
+        let dev: Option<u64> = self.parse_dev();
+        let local: Vec<PipLocalSegment> = self.parse_local()?;
+
+        if self.position != self.string.len() {
+            return None;
+        }
+
+        let version: PipVersion = PipVersion {
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+            local,
+            legacy: None,
+        };
+        Some(version)
+    }
+
+    fn parse_pre(&mut self) -> Option<(PipPreRelease, u64)> {
+        let start: usize = self.position;
+        self.consume_separator();
+
+        let labels: [&str; 7] = ["preview", "alpha", "beta", "pre", "rc", "a", "b"];
+        let label: Option<&str> = labels.into_iter().find(|label| self.consume(label));
+        let label: Option<&str> = label.or_else(|| self.consume("c").then_some("c"));
+
+        if let Some(label) = label {
+            let kind: PipPreRelease = PipPreRelease::from_string(label)?;
+            let number: u64 = self.parse_optional_number();
+            return Some((kind, number));
+        }
+        self.position = start;
+        None
+    }
+
+    fn parse_post(&mut self) -> Option<u64> {
+        let start: usize = self.position;
+
+        // Implicit post release, e.g. "1.0-1".
+        if self.consume("-") {
+            if let Some(number) = self.parse_number() {
+                return Some(number);
+            }
+            self.position = start;
+        }
+
+        self.consume_separator();
+        let labels: [&str; 3] = ["post", "rev", "r"];
+        if labels.into_iter().any(|label| self.consume(label)) {
+            return Some(self.parse_optional_number());
+        }
+        self.position = start;
+        None
+    }
+
+    fn parse_dev(&mut self) -> Option<u64> {
+        let start: usize = self.position;
+        self.consume_separator();
+        if self.consume("dev") {
+            return Some(self.parse_optional_number());
+        }
+        self.position = start;
+        None
+    }
+
+    fn parse_local(&mut self) -> Option<Vec<PipLocalSegment>> {
+        let mut local: Vec<PipLocalSegment> = Vec::new();
+        if !self.consume("+") {
+            return Some(local);
+        }
+
+        let rest: &str = &self.string[self.position..];
+        for segment in rest.split(['.', '-', '_']) {
+            if segment.is_empty() || !segment.chars().all(|char| char.is_ascii_alphanumeric()) {
+                return None;
+            }
+            let segment: PipLocalSegment = match segment.parse::<u64>() {
+                Ok(number) => PipLocalSegment::Number(number),
+                Err(_) => PipLocalSegment::Text(segment.to_string()),
+            };
+            local.push(segment);
+        }
+        self.position = self.string.len();
+        Some(local)
+    }
+
+    fn parse_optional_number(&mut self) -> u64 {
+        let start: usize = self.position;
+        self.consume_separator();
+        if let Some(number) = self.parse_number() {
+            return number;
+        }
+        self.position = start;
+        0
+    }
+
+    fn parse_number(&mut self) -> Option<u64> {
+        let rest: &str = &self.string[self.position..];
+        let length: usize = rest.chars().take_while(char::is_ascii_digit).count();
+        if length == 0 {
+            return None;
+        }
+        let number: u64 = rest[..length].parse().ok()?;
+        self.position += length;
+        Some(number)
+    }
+
+    fn consume_separator(&mut self) {
+        let _ = self.consume(".") || self.consume("-") || self.consume("_");
+    }
+
+    fn consume(&mut self, token: &str) -> bool {
+        if self.string[self.position..].starts_with(token) {
+            self.position += token.len();
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_version(string: &str) -> PipVersion {
+        PipVersion::from_string(string).unwrap()
+    }
+
+    fn contains(specifier: &str, version: &str) -> bool {
+        let split: usize = specifier
+            .find(|char: char| !"<>=!~".contains(char))
+            .unwrap();
+        let operator: PipOperator = PipOperator::from_string(&specifier[..split]).unwrap();
+        let specifier: PipSpecifier = PipSpecifier::new(operator, &specifier[split..]);
+        specifier.contains(&PipVersion::from_string_or_legacy(version))
+    }
+
+    #[test]
+    fn orders_pre_dev_and_post_releases() {
+        let versions: Vec<PipVersion> = ["1.0.dev1", "1.0a1", "1.0rc1", "1.0", "1.0.post1"]
+            .iter()
+            .map(|string| get_version(string))
+            .collect();
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(get_version("1.0"), get_version("1.0.0"));
+        assert_eq!(get_version("1.0-RC.1"), get_version("1.0rc1"));
+    }
+
+    #[test]
+    fn orders_epochs_before_releases() {
+        let version: PipVersion = get_version("1!2.0");
+        assert_eq!(version.get_string(), "1!2.0");
+        assert!(version > get_version("2024.1"));
+        assert!(contains(">=1!1.0", "1!2.0"));
+        assert!(!contains(">=1!1.0", "3.0"));
+        assert!(!contains("==2.*", "1!2.0"));
+    }
+
+    #[test]
+    fn ignores_local_segments_unless_specified() {
+        let version: PipVersion = get_version("1.0+abc");
+        assert!(version.has_local());
+        assert!(version > get_version("1.0"));
+        assert_eq!(version.get_public(), get_version("1.0"));
+        assert!(contains("==1.0", "1.0+abc"));
+        assert!(contains("==1.0+abc", "1.0+abc"));
+        assert!(!contains("==1.0+def", "1.0+abc"));
+    }
+
+    #[test]
+    fn matches_compatible_releases() {
+        assert!(contains("~=2.2", "2.2"));
+        assert!(contains("~=2.2", "2.9.1"));
+        assert!(!contains("~=2.2", "3.0"));
+        assert!(!contains("~=2.2", "2.1"));
+        assert!(contains("~=1.4.5", "1.4.9"));
+        assert!(!contains("~=1.4.5", "1.5.0"));
+        assert!(!contains("~=1.4.5", "1.4.4"));
+    }
+
+    #[test]
+    fn matches_wildcard_prefixes() {
+        assert!(contains("==1.*", "1.0"));
+        assert!(contains("==1.*", "1.9.post1"));
+        assert!(!contains("==1.*", "2.0"));
+        assert!(!contains("!=1.*", "1.4"));
+        assert!(contains("!=1.*", "2.0"));
+    }
+
+    #[test]
+    fn excludes_pre_and_post_releases_of_the_bound() {
+        assert!(!contains("<1.0", "1.0rc1"));
+        assert!(!contains("<1.0", "1.0.dev1"));
+        assert!(contains("<1.0", "0.9rc1"));
+        assert!(contains("<1.0rc2", "1.0rc1"));
+        assert!(!contains(">1.0", "1.0.post1"));
+        assert!(contains(">1.0", "1.1"));
+        assert!(contains(">1.0.post1", "1.0.post2"));
+    }
+
+    #[test]
+    fn falls_back_to_legacy_versions() {
+        assert!(PipVersion::from_string("not-a-version").is_none());
+        let legacy: PipVersion = PipVersion::from_string_or_legacy("not-a-version");
+        assert!(legacy.is_legacy());
+        assert_eq!(legacy.get_string(), "not-a-version");
+        assert!(legacy < get_version("0.0.1"));
+        assert!(!contains(">=0", "not-a-version"));
+        assert!(!contains("==1.*", "not-a-version"));
+        assert!(contains("===not-a-version", "not-a-version"));
+    }
+}
@@ -0,0 +1,633 @@
+use super::pep440::{PipOperator, PipSpecifier, PipVersion};
+use super::pip::PipPackageName;
+
+use crate::misc::version::SemanticVersion;
+
+/// Marker variables PEP 508 compares with PEP 440 rules. Others, such as the free-form
+/// `platform_version`, compare as strings.
+const VERSION_VARIABLES: [&str; 3] = [
+    "python_version",
+    "python_full_version",
+    "implementation_version",
+];
+
+#[derive(Debug, Clone)]
+pub enum PipMarkerValue {
+    Variable(String),
+    Literal(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipMarkerOperator {
+    Version(PipOperator),
+    In,
+    NotIn,
+}
+
+#[derive(Debug, Clone)]
+pub enum PipMarker {
+    And(Box<PipMarker>, Box<PipMarker>),
+    Or(Box<PipMarker>, Box<PipMarker>),
+    Compare(PipMarkerValue, PipMarkerOperator, PipMarkerValue),
+}
+
+impl PipMarker {
+    pub fn evaluate(&self, environment: &PipMarkerEnvironment) -> bool {
+        match self {
+            PipMarker::And(left, right) => {
+                left.evaluate(environment) && right.evaluate(environment)
+            }
+            PipMarker::Or(left, right) => left.evaluate(environment) || right.evaluate(environment),
+            PipMarker::Compare(left, operator, right) => {
+                Self::evaluate_compare(left, operator, right, environment)
+            }
+        }
+    }
+
+    /// Returns every extra the marker compares against, e.g. `socks` and `all` for
+    /// `extra == "socks" or extra == "all"`.
+    pub fn get_extras(&self) -> Vec<String> {
+        match self {
+            PipMarker::And(left, right) | PipMarker::Or(left, right) => {
+                let mut extras: Vec<String> = left.get_extras();
+                for extra in right.get_extras() {
+                    if !extras.contains(&extra) {
+                        extras.push(extra);
+                    }
+                }
+                extras
+            }
+            PipMarker::Compare(left, _, right) => match (left, right) {
+                (PipMarkerValue::Variable(name), PipMarkerValue::Literal(extra))
+                | (PipMarkerValue::Literal(extra), PipMarkerValue::Variable(name))
+                    if name == "extra" =>
+                {
+                    vec![PipPackageName::new(extra).get_string().to_string()]
+                }
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    /// Evaluates only the `extra` comparisons, treating every other comparison as met.
+    /// Used once the environment markers have already been applied.
+    pub fn evaluate_extra(&self, extra: Option<&str>) -> bool {
+        match self {
+            PipMarker::And(left, right) => {
+                left.evaluate_extra(extra) && right.evaluate_extra(extra)
+            }
+            PipMarker::Or(left, right) => left.evaluate_extra(extra) || right.evaluate_extra(extra),
+            PipMarker::Compare(left, operator, right) => {
+                let is_extra: bool =
+                    Self::is_variable(left, &["extra"]) || Self::is_variable(right, &["extra"]);
+                if !is_extra {
+                    return true;
+                }
+                let resolve = |value: &PipMarkerValue| -> Option<String> {
+                    match value {
+                        PipMarkerValue::Variable(_) => extra.map(|extra| extra.to_string()),
+                        PipMarkerValue::Literal(literal) => Some(literal.clone()),
+                    }
+                };
+                match (resolve(left), resolve(right)) {
+                    (Some(left), Some(right)) => Self::compare_extras(&left, operator, &right),
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+impl PipMarker {
+    fn evaluate_compare(
+        left: &PipMarkerValue,
+        operator: &PipMarkerOperator,
+        right: &PipMarkerValue,
+        environment: &PipMarkerEnvironment,
+    ) -> bool {
+        let is_extra: bool =
+            Self::is_variable(left, &["extra"]) || Self::is_variable(right, &["extra"]);
+        let is_version: bool = Self::is_variable(left, &VERSION_VARIABLES)
+            || Self::is_variable(right, &VERSION_VARIABLES);
+
+        let left: Option<String> = environment.resolve(left);
+        let right: Option<String> = environment.resolve(right);
+        let (left, right): (String, String) = match (left, right) {
+            (Some(left), Some(right)) => (left, right),
+            _ => return false,
+        };
+
+        if is_extra {
+            return Self::compare_extras(&left, operator, &right);
+        }
+
+        match operator {
+            PipMarkerOperator::In => right.contains(&left),
+            PipMarkerOperator::NotIn => !right.contains(&left),
+            PipMarkerOperator::Version(operator) if is_version => {
+                Self::compare_versions(&left, operator, &right)
+            }
+            PipMarkerOperator::Version(operator) => match operator {
+                PipOperator::Equal | PipOperator::Arbitrary => left == right,
+                PipOperator::NotEqual => left != right,
+                PipOperator::Less => left < right,
+                PipOperator::LessEqual => left <= right,
+                PipOperator::Greater => left > right,
+                PipOperator::GreaterEqual => left >= right,
+                PipOperator::Compatible => false,
+            },
+        }
+    }
+
+    /// Extras compare by their normalized names.
+    fn compare_extras(left: &str, operator: &PipMarkerOperator, right: &str) -> bool {
+        let left: PipPackageName = PipPackageName::new(left);
+        let right: PipPackageName = PipPackageName::new(right);
+        match operator {
+            PipMarkerOperator::Version(PipOperator::NotEqual) => left != right,
+            PipMarkerOperator::Version(_) => left == right,
+            PipMarkerOperator::In => right.get_string().contains(left.get_string()),
+            PipMarkerOperator::NotIn => !right.get_string().contains(left.get_string()),
+        }
+    }
+
+    fn is_variable(value: &PipMarkerValue, names: &[&str]) -> bool {
+        matches!(value, PipMarkerValue::Variable(name) if names.contains(&name.as_str()))
+    }
+
+    fn compare_versions(left: &str, operator: &PipOperator, right: &str) -> bool {
+        let left: PipVersion = PipVersion::from_string_or_legacy(left);
+        let specifier: PipSpecifier = PipSpecifier::new(operator.clone(), right);
+        specifier.contains(&left)
+    }
+}
+
+#[derive(Clone)]
+pub struct PipMarkerEnvironment {
+    python_version: SemanticVersion,
+    extra: Option<String>,
+}
+
+impl PipMarkerEnvironment {
+    pub fn new(python_version: &SemanticVersion) -> Self {
+        let python_version: SemanticVersion = python_version.clone();
+        PipMarkerEnvironment {
+            python_version,
+            extra: None,
+        }
+    }
+
+    pub fn set_extra(&mut self, extra: Option<&str>) {
+        self.extra = extra.map(|extra| extra.to_string());
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<String> {
+        let variable: String = match name {
+            "python_version" => self.python_version.get_2p_string(),
+            "python_full_version" | "implementation_version" => self.python_version.get_3p_string(),
+            "os_name" => Self::get_os_name().to_string(),
+            "sys_platform" => Self::get_sys_platform().to_string(),
+            "platform_system" => Self::get_platform_system().to_string(),
+            "platform_machine" => Self::get_platform_machine().to_string(),
+            "implementation_name" => "cpython".to_string(),
+            "platform_python_implementation" => "CPython".to_string(),
+            "platform_release" | "platform_version" => String::new(),
+            "extra" => self.extra.clone()?,
+            _ => return None,
+        };
+        Some(variable)
+    }
+}
+
+impl PipMarkerEnvironment {
+    fn resolve(&self, value: &PipMarkerValue) -> Option<String> {
+        match value {
+            PipMarkerValue::Variable(name) => self.get_variable(name),
+            PipMarkerValue::Literal(literal) => Some(literal.clone()),
+        }
+    }
+
+    fn get_os_name() -> &'static str {
+        if cfg!(windows) {
+            return "nt";
+        }
+        "posix"
+    }
+
+    fn get_sys_platform() -> &'static str {
+        match std::env::consts::OS {
+            "windows" => "win32",
+            "macos" => "darwin",
+            os => os,
+        }
+    }
+
+    fn get_platform_system() -> &'static str {
+        match std::env::consts::OS {
+            "windows" => "Windows",
+            "macos" => "Darwin",
+            "linux" => "Linux",
+            "freebsd" => "FreeBSD",
+            os => os,
+        }
+    }
+
+    fn get_platform_machine() -> &'static str {
+        match (std::env::consts::OS, std::env::consts::ARCH) {
+            ("windows", "x86_64") => "AMD64",
+            ("windows", "aarch64") => "ARM64",
+            ("windows", "x86") => "x86",
+            ("macos", "aarch64") => "arm64",
+            (_, arch) => arch,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PipRequirement {
+    name: PipPackageName,
+    extras: Vec<String>,
+    specifiers: Vec<PipSpecifier>,
+    url: Option<String>,
+    marker: Option<PipMarker>,
+}
+
+impl PipRequirement {
+    /// Parses a PEP 508 dependency specification such as `requests[socks]>=2.0; python_version<"3.8"`.
+    pub fn from_string(string: &str) -> Option<Self> {
+        let mut parser: Pep508Parser = Pep508Parser::new(string);
+        let requirement: PipRequirement = parser.parse_requirement()?;
+        parser.skip_whitespace();
+        if !parser.is_finished() {
+            return None;
+        }
+        Some(requirement)
+    }
+
+    pub fn get_name(&self) -> &PipPackageName {
+        &self.name
+    }
+
+    pub fn get_extras(&self) -> &Vec<String> {
+        &self.extras
+    }
+
+    pub fn get_specifiers(&self) -> &Vec<PipSpecifier> {
+        &self.specifiers
+    }
+
+    pub fn get_url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    pub fn get_marker(&self) -> Option<&PipMarker> {
+        self.marker.as_ref()
+    }
+
+    /// Returns the extras named in the marker, empty for base requirements.
+    pub fn get_marker_extras(&self) -> Vec<String> {
+        self.marker
+            .as_ref()
+            .map(|marker| marker.get_extras())
+            .unwrap_or_default()
+    }
+
+    /// Returns whether requesting `extra` pulls this requirement in, or with `None`,
+    /// whether the package needs it without any extra.
+    pub fn is_required_by(&self, extra: Option<&str>) -> bool {
+        match &self.marker {
+            Some(marker) => marker.evaluate_extra(extra),
+            None => true,
+        }
+    }
+
+    pub fn is_applicable(&self, environment: &PipMarkerEnvironment) -> bool {
+        match &self.marker {
+            Some(marker) => marker.evaluate(environment),
+            None => true,
+        }
+    }
+
+    pub fn get_specifier_string(&self) -> String {
+        let specifiers: Vec<String> = self
+            .specifiers
+            .iter()
+            .map(|specifier| specifier.get_string())
+            .collect();
+        specifiers.join(",")
+    }
+}
+
+struct Pep508Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Pep508Parser {
+    fn new(string: &str) -> Self {
+        let chars: Vec<char> = string.chars().collect();
+        Pep508Parser { chars, position: 0 }
+    }
+
+    fn parse_requirement(&mut self) -> Option<PipRequirement> {
+        self.skip_whitespace();
+        let name: String = self.parse_identifier()?;
+        let name: PipPackageName = PipPackageName::new(&name);
+
+        self.skip_whitespace();
+        let extras: Vec<String> = self.parse_extras()?;
+
+        self.skip_whitespace();
+        let mut specifiers: Vec<PipSpecifier> = Vec::new();
+        let mut url: Option<String> = None;
+
+        if self.consume("@") {
+            self.skip_whitespace();
+            url = Some(self.parse_url()?);
+        } else {
+            specifiers = self.parse_specifiers()?;
+        }
+
+        self.skip_whitespace();
+        let mut marker: Option<PipMarker> = None;
+        if self.consume(";") {
+            marker = Some(self.parse_marker_or()?);
+        }
+
+        let requirement: PipRequirement = PipRequirement {
+            name,
+            extras,
+            specifiers,
+            url,
+            marker,
+        };
+        Some(requirement)
+    }
+
+    fn parse_identifier(&mut self) -> Option<String> {
+        let start: usize = self.position;
+        while let Some(char) = self.peek() {
+            let is_inner: bool = self.position > start && matches!(char, '-' | '_' | '.');
+            if char.is_ascii_alphanumeric() || is_inner {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        let identifier: String = self.chars[start..self.position].iter().collect();
+        let identifier: &str = identifier.trim_end_matches(['-', '_', '.']);
+        self.position = start + identifier.chars().count();
+
+        if identifier.is_empty() {
+            return None;
+        }
+        Some(identifier.to_string())
+    }
+
+    fn parse_extras(&mut self) -> Option<Vec<String>> {
+        let mut extras: Vec<String> = Vec::new();
+        if !self.consume("[") {
+            return Some(extras);
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.consume("]") {
+                return Some(extras);
+            }
+
+            let extra: String = self.parse_identifier()?;
+            extras.push(PipPackageName::new(&extra).get_string().to_string());
+
+            self.skip_whitespace();
+            if !self.consume(",") {
+                self.skip_whitespace();
+                return self.consume("]").then_some(extras);
+            }
+        }
+    }
+
+    fn parse_specifiers(&mut self) -> Option<Vec<PipSpecifier>> {
+        let parenthesized: bool = self.consume("(");
+        let mut specifiers: Vec<PipSpecifier> = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            let operator: Option<PipOperator> = self.parse_operator();
+            let operator: PipOperator = match operator {
+                Some(operator) => operator,
+                None if specifiers.is_empty() => break,
+                None => return None,
+            };
+
+            self.skip_whitespace();
+            let version: String = self.take_while(|char| {
+                char.is_ascii_alphanumeric() || matches!(char, '.' | '*' | '+' | '!' | '-' | '_')
+            });
+            if version.is_empty() {
+                return None;
+            }
+            specifiers.push(PipSpecifier::new(operator, &version));
+
+            self.skip_whitespace();
+            if !self.consume(",") {
+                break;
+            }
+        }
+
+        if parenthesized {
+            self.skip_whitespace();
+            if !self.consume(")") {
+                return None;
+            }
+        }
+        Some(specifiers)
+    }
+
+    fn parse_operator(&mut self) -> Option<PipOperator> {
+        let operators: [&str; 8] = ["===", "~=", "==", "!=", "<=", ">=", "<", ">"];
+        for operator in operators {
+            if self.consume(operator) {
+                return PipOperator::from_string(operator);
+            }
+        }
+        None
+    }
+
+    fn parse_url(&mut self) -> Option<String> {
+        // A marker after a URL must be separated by whitespace, so a trailing ";" is part of it.
+        let url: String = self.take_while(|char| !char.is_whitespace());
+        if url.is_empty() {
+            return None;
+        }
+        Some(url)
+    }
+
+    fn parse_marker_or(&mut self) -> Option<PipMarker> {
+        let mut marker: PipMarker = self.parse_marker_and()?;
+        loop {
+            self.skip_whitespace();
+            if !self.consume_keyword("or") {
+                return Some(marker);
+            }
+            let right: PipMarker = self.parse_marker_and()?;
+            marker = PipMarker::Or(Box::new(marker), Box::new(right));
+        }
+    }
+
+    fn parse_marker_and(&mut self) -> Option<PipMarker> {
+        let mut marker: PipMarker = self.parse_marker_atom()?;
+        loop {
+            self.skip_whitespace();
+            if !self.consume_keyword("and") {
+                return Some(marker);
+            }
+            let right: PipMarker = self.parse_marker_atom()?;
+            marker = PipMarker::And(Box::new(marker), Box::new(right));
+        }
+    }
+
+    fn parse_marker_atom(&mut self) -> Option<PipMarker> {
+        self.skip_whitespace();
+        if self.consume("(") {
+            let marker: PipMarker = self.parse_marker_or()?;
+            self.skip_whitespace();
+            return self.consume(")").then_some(marker);
+        }
+
+        let left: PipMarkerValue = self.parse_marker_value()?;
+        self.skip_whitespace();
+        let operator: PipMarkerOperator = self.parse_marker_operator()?;
+        self.skip_whitespace();
+        let right: PipMarkerValue = self.parse_marker_value()?;
+        Some(PipMarker::Compare(left, operator, right))
+    }
+
+    fn parse_marker_value(&mut self) -> Option<PipMarkerValue> {
+        let quote: char = self.peek()?;
+        if quote == '"' || quote == '\'' {
+            self.position += 1;
+            let literal: String = self.take_while(|char| char != quote);
+            if !self.consume(&quote.to_string()) {
+                return None;
+            }
+            return Some(PipMarkerValue::Literal(literal));
+        }
+
+        let variable: String = self.take_while(|char| char.is_ascii_alphanumeric() || char == '_');
+        if variable.is_empty() {
+            return None;
+        }
+        Some(PipMarkerValue::Variable(variable))
+    }
+
+    fn parse_marker_operator(&mut self) -> Option<PipMarkerOperator> {
+        if self.consume_keyword("in") {
+            return Some(PipMarkerOperator::In);
+        }
+        if self.consume_keyword("not") {
+            self.skip_whitespace();
+            return self
+                .consume_keyword("in")
+                .then_some(PipMarkerOperator::NotIn);
+        }
+        let operator: PipOperator = self.parse_operator()?;
+        Some(PipMarkerOperator::Version(operator))
+    }
+
+    fn consume(&mut self, token: &str) -> bool {
+        let token: Vec<char> = token.chars().collect();
+        let end: usize = self.position + token.len();
+        if end <= self.chars.len() && self.chars[self.position..end] == token[..] {
+            self.position = end;
+            return true;
+        }
+        false
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        let start: usize = self.position;
+        if self.consume(keyword) {
+            let boundary: bool =
+                !matches!(self.peek(), Some(char) if char.is_ascii_alphanumeric() || char == '_');
+            if boundary {
+                return true;
+            }
+        }
+        self.position = start;
+        false
+    }
+
+    fn take_while<F>(&mut self, predicate: F) -> String
+    where
+        F: Fn(char) -> bool,
+    {
+        let start: usize = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.position >= self.chars.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_requirement(string: &str) -> PipRequirement {
+        PipRequirement::from_string(string).unwrap()
+    }
+
+    #[test]
+    fn collects_every_extra_of_an_or_chain() {
+        let requirement: PipRequirement =
+            get_requirement(r#"PySocks>=1.5.6; extra == "socks" or extra == "All""#);
+        assert_eq!(requirement.get_marker_extras(), vec!["socks", "all"]);
+        assert!(requirement.is_required_by(Some("socks")));
+        assert!(requirement.is_required_by(Some("all")));
+        assert!(!requirement.is_required_by(Some("http2")));
+        assert!(!requirement.is_required_by(None));
+    }
+
+    #[test]
+    fn ignores_environment_markers_when_matching_extras() {
+        let requirement: PipRequirement =
+            get_requirement(r#"colorama; sys_platform == "win32" and extra == "cli""#);
+        assert!(requirement.is_required_by(Some("cli")));
+        assert!(!requirement.is_required_by(None));
+
+        let requirement: PipRequirement = get_requirement(r#"idna; python_version < "3.8""#);
+        assert!(requirement.get_marker_extras().is_empty());
+        assert!(requirement.is_required_by(None));
+    }
+
+    #[test]
+    fn compares_only_version_variables_as_versions() {
+        let environment: PipMarkerEnvironment =
+            PipMarkerEnvironment::new(&SemanticVersion::new_3p(3, 10, 4));
+
+        let requirement: PipRequirement = get_requirement(r#"tomli; python_version < "3.9""#);
+        assert!(!requirement.is_applicable(&environment));
+        let requirement: PipRequirement = get_requirement(r#"tomli; python_version >= "3.9""#);
+        assert!(requirement.is_applicable(&environment));
+
+        // `platform_version` is free-form text and compares as a string.
+        let requirement: PipRequirement = get_requirement(r#"pywin32; platform_version == "10""#);
+        assert!(!requirement.is_applicable(&environment));
+        let requirement: PipRequirement = get_requirement(r#"pywin32; platform_version != "10""#);
+        assert!(requirement.is_applicable(&environment));
+    }
+}
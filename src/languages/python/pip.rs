@@ -1,13 +1,20 @@
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::fs::File;
 use std::fs::ReadDir;
+use std::hash::{Hash, Hasher};
 use std::io;
 use std::io::BufRead;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use super::dependency::PipDependencyGraph;
+use super::dist_info::{PipDirectUrl, PipDistInfo};
+use super::pep440::PipVersion;
+use super::pep508::{PipMarkerEnvironment, PipRequirement};
 use super::python::PythonEnvironment;
 
+use crate::misc::file_url::FileUrl;
 use crate::misc::path::WPath;
 use crate::misc::shell::{CommandExecute, CommandResponse};
 use crate::misc::version::SemanticVersion;
@@ -194,6 +201,11 @@ impl PipShow {
         }
         Some(&self.name)
     }
+
+    pub fn get_package_name(&self) -> Option<PipPackageName> {
+        let name: &str = self.get_name()?;
+        Some(PipPackageName::new(name))
+    }
 }
 
 impl PipShow {
@@ -219,15 +231,38 @@ impl PipShow {
 
 pub struct PipMetadata {
     package: PipPackage,
+    requirements: Vec<PipRequirement>,
     requires: HashMap<String, HashSet<PipPackageName>>,
 }
 
 impl PipMetadata {
-    pub fn new(package: &PipPackage, requires: &HashMap<String, HashSet<PipPackageName>>) -> Self {
+    pub fn new(package: &PipPackage, requirements: &[PipRequirement]) -> Self {
         let package: PipPackage = package.clone();
-        let requires: HashMap<String, HashSet<PipPackageName>> = requires.clone();
+        let requirements: Vec<PipRequirement> = requirements.to_vec();
+        let mut requires: HashMap<String, HashSet<PipPackageName>> = HashMap::new();
+
+        for requirement in requirements.iter() {
+            let mut flag_names: Vec<String> = requirement
+                .get_marker_extras()
+                .into_iter()
+                .filter(|extra| requirement.is_required_by(Some(extra)))
+                .collect();
+            if requirement.is_required_by(None) {
+                flag_names.push("base".to_string());
+            }
+
+            for flag_name in flag_names {
+                let package_names: &mut HashSet<PipPackageName> =
+                    requires.entry(flag_name).or_default();
+                package_names.insert(requirement.get_name().clone());
+            }
+        }
 
-        PipMetadata { package, requires }
+        PipMetadata {
+            package,
+            requirements,
+            requires,
+        }
     }
 
     #[allow(dead_code)]
@@ -235,112 +270,173 @@ impl PipMetadata {
         &self.package
     }
 
+    #[allow(dead_code)]
+    pub fn get_requirements(&self) -> &Vec<PipRequirement> {
+        &self.requirements
+    }
+
     pub fn get_requires(&self) -> &HashMap<String, HashSet<PipPackageName>> {
         &self.requires
     }
 }
 
-struct PipMetadataParser;
+struct PipMetadataParser<'a> {
+    marker_environment: Option<&'a PipMarkerEnvironment>,
+}
 
-impl PipMetadataParser {
-    pub fn new() -> Self {
-        PipMetadataParser
+impl<'a> PipMetadataParser<'a> {
+    pub fn new(marker_environment: Option<&'a PipMarkerEnvironment>) -> Self {
+        PipMetadataParser { marker_environment }
     }
 
-    fn parse_requires(
-        &self,
-        reader: io::BufReader<File>,
-    ) -> HashMap<String, HashSet<PipPackageName>> {
-        let mut flag_name: String = "base".to_string();
-        let mut requires: HashMap<String, HashSet<PipPackageName>> = HashMap::new();
-        let mut package_names: HashSet<PipPackageName> = HashSet::new();
-        let mut requires_begin: bool = false;
+    fn parse_requires(&self, reader: io::BufReader<File>) -> Vec<PipRequirement> {
+        let mut requirements: Vec<PipRequirement> = Vec::new();
         let requires_dist: &str = "Requires-Dist:";
-        let provides_extra: &str = "Provides-Extra:";
 
-        for line in reader.lines() {
-            if let Ok(line) = line {
-                if line.starts_with(provides_extra) && !requires_begin {
-                    flag_name = self.parse_flag_name(&line, provides_extra);
-                } else if line.starts_with(&provides_extra) && requires_begin {
-                    requires.insert(flag_name.to_string(), package_names.clone());
-                    package_names.clear();
-                    flag_name = self.parse_flag_name(&line, provides_extra);
-                } else if line.starts_with(requires_dist) {
-                    requires_begin = true;
-                    let name: PipPackageName = self.parse_requires_name(&line, requires_dist);
-                    package_names.insert(name);
-                } else if requires_begin && line.is_empty() {
-                    requires.insert(flag_name.to_string(), package_names.clone());
-                    package_names.clear();
-                    break;
-                }
+        for line in reader.lines().map_while(Result::ok) {
+            // Headers end at the first empty line, the description body follows.
+            if line.trim().is_empty() {
+                break;
             }
-        }
 
-        if !package_names.is_empty() {
-            requires.insert(flag_name.to_string(), package_names.clone());
+            if let Some(requirement_string) = line.strip_prefix(requires_dist) {
+                let requirement: Option<PipRequirement> =
+                    PipRequirement::from_string(requirement_string);
+                if let Some(requirement) = requirement {
+                    if self.is_applicable(&requirement) {
+                        requirements.push(requirement);
+                    }
+                }
+            }
         }
 
-        requires
+        requirements
     }
 }
 
-impl PipMetadataParser {
-    fn parse_flag_name(&self, line: &str, provides_extra: &str) -> String {
-        let line: &str = line.trim_start_matches(provides_extra);
-        let line: &str = line.trim();
-        let provides_name: String = line.to_string();
-        provides_name
-    }
-
-    fn parse_requires_name(&self, line: &str, requires_dist: &str) -> PipPackageName {
-        let line: &str = line.trim_start_matches(requires_dist);
-        let line: &str = line.trim();
-        let mut chars: Vec<char> = Vec::new();
-        for character in line.chars() {
-            if character.is_whitespace() {
-                break;
-            }
-            chars.push(character);
+impl<'a> PipMetadataParser<'a> {
+    fn is_applicable(&self, requirement: &PipRequirement) -> bool {
+        if let Some(marker_environment) = self.marker_environment {
+            // Keep a requirement that applies without extras or with any extra it names.
+            let mut marker_environment: PipMarkerEnvironment = marker_environment.clone();
+            let extras: Vec<String> = requirement.get_marker_extras();
+            let extras: Vec<Option<&str>> = std::iter::once(None)
+                .chain(extras.iter().map(|extra| Some(extra.as_str())))
+                .collect();
+
+            return extras.into_iter().any(|extra| {
+                marker_environment.set_extra(extra);
+                requirement.is_applicable(&marker_environment)
+            });
         }
-        let string: String = chars.into_iter().collect();
-        let name: PipPackageName = PipPackageName::new(&string);
-        name
+        true
     }
 }
 
-#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct PipPackageName {
+    raw: String,
     name: String,
 }
+
 impl PipPackageName {
     pub fn new(name: &str) -> Self {
-        let name: String = name.replace("_", "-").to_lowercase();
-        PipPackageName { name }
+        let raw: String = name.trim().to_string();
+        let name: String = Self::normalize(&raw);
+        PipPackageName { raw, name }
     }
 
+    /// Returns the PEP 503 normalized name used for comparisons.
     pub fn get_string(&self) -> &str {
         &self.name
     }
+
+    /// Returns the name as it was spelled by the package.
+    pub fn get_raw_string(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl PipPackageName {
+    fn normalize(name: &str) -> String {
+        let mut normalized: String = String::with_capacity(name.len());
+        let mut in_separator: bool = false;
+
+        for char in name.chars() {
+            if matches!(char, '-' | '_' | '.') {
+                in_separator = true;
+                continue;
+            }
+            if in_separator && !normalized.is_empty() {
+                normalized.push('-');
+            }
+            in_separator = false;
+            normalized.push(char.to_ascii_lowercase());
+        }
+        normalized
+    }
+}
+
+impl PartialEq for PipPackageName {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for PipPackageName {}
+
+impl Hash for PipPackageName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl PartialOrd for PipPackageName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PipPackageName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name.cmp(&other.name)
+    }
 }
 
 #[derive(Clone)]
 pub struct PipPackage {
     name: PipPackageName,
-    version: SemanticVersion,
+    version: PipVersion,
     path: WPath,
+    extras: Vec<String>,
+    dist_info: PipDistInfo,
 }
 
 impl PipPackage {
-    pub fn new(name: PipPackageName, version: SemanticVersion, path: WPath) -> Self {
+    pub fn new(name: PipPackageName, version: PipVersion, path: WPath) -> Self {
+        let extras: Vec<String> = Vec::new();
+        let dist_info: PipDistInfo = PipDistInfo::new(&path);
         PipPackage {
             name,
             version,
             path,
+            extras,
+            dist_info,
         }
     }
 
+    /// Sets the extras the package should be requested with when it is written out.
+    pub fn set_extras(&mut self, extras: &[String]) {
+        let mut extras: Vec<String> = extras.to_vec();
+        extras.sort();
+        extras.dedup();
+        self.extras = extras;
+    }
+
+    pub fn get_extras(&self) -> &Vec<String> {
+        &self.extras
+    }
+
     pub fn get_name(&self) -> &PipPackageName {
         &self.name
     }
@@ -351,7 +447,7 @@ impl PipPackage {
     }
 
     #[allow(dead_code)]
-    pub fn get_version(&self) -> &SemanticVersion {
+    pub fn get_version(&self) -> &PipVersion {
         &self.version
     }
 
@@ -360,39 +456,141 @@ impl PipPackage {
         &self.path
     }
 
+    pub fn get_dist_info(&self) -> &PipDistInfo {
+        &self.dist_info
+    }
+
+    /// Returns the directory the package is installed into, or the source
+    /// directory of an editable install.
+    pub fn get_location(&self) -> WPath {
+        let direct_url: Option<&PipDirectUrl> = self.dist_info.get_direct_url();
+        if let Some(direct_url) = direct_url.filter(|direct_url| direct_url.is_editable()) {
+            if let Some(path) = FileUrl::get_local_path(direct_url.get_url()) {
+                return WPath::from_path_buf(&path);
+            }
+        }
+
+        let parent: Option<&Path> = self.path.get_path_buf().parent();
+        parent
+            .map(WPath::from_path)
+            .unwrap_or_else(|| self.path.clone())
+    }
+
     pub fn get_string(&self) -> String {
         let version_string: String = self.version.get_string();
-        let name: &str = self.name.get_string();
+        let name: String = self.get_name_with_extras();
         let string: String = format!("Name: {} | Version: {}", name, version_string);
         string
     }
 
     pub fn get_requirement_string(&self) -> String {
         let version_string: String = self.version.get_string();
-        let name: &str = self.name.get_string();
+        let name: String = self.get_name_with_extras();
         let string: String = format!("{}=={}", name, version_string);
         string
     }
 }
 
+impl PipPackage {
+    fn get_name_with_extras(&self) -> String {
+        let name: &str = self.name.get_raw_string();
+        if self.extras.is_empty() {
+            return name.to_string();
+        }
+        format!("{}[{}]", name, self.extras.join(","))
+    }
+}
+
 impl Debug for PipPackage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PipPackage")
             .field("name", &self.name.get_string())
             .field("version", &self.version)
             .field("path", &self.path)
+            .field("extras", &self.extras)
             .finish()
     }
 }
 
+pub struct PipRequirementsParser;
+
+impl PipRequirementsParser {
+    pub fn new() -> Self {
+        PipRequirementsParser
+    }
+
+    /// Reads the requirements of a requirements file. Comments, pip options such as
+    /// `-r`, `-e` or `--index-url` and per-requirement options such as `--hash` are ignored.
+    pub fn parse(&self, file_path: &WPath) -> Result<Vec<PipRequirement>, io::Error> {
+        let file: File = File::open(file_path)?;
+        let reader: io::BufReader<File> = io::BufReader::new(file);
+        let mut requirements: Vec<PipRequirement> = Vec::new();
+        let mut logical_line: String = String::new();
+
+        for line in reader.lines() {
+            let line: String = line?;
+            // A trailing backslash joins the line with the next one.
+            if let Some(continued) = line.strip_suffix('\\') {
+                logical_line.push_str(continued);
+                continue;
+            }
+            logical_line.push_str(&line);
+
+            let requirement_string: String = std::mem::take(&mut logical_line);
+            if let Some(requirement) = self.parse_line(&requirement_string) {
+                requirements.push(requirement);
+            }
+        }
+
+        if let Some(requirement) = self.parse_line(&logical_line) {
+            requirements.push(requirement);
+        }
+        Ok(requirements)
+    }
+}
+
+impl PipRequirementsParser {
+    fn parse_line(&self, line: &str) -> Option<PipRequirement> {
+        let line: &str = match line.find(" #") {
+            Some(idx) => &line[..idx],
+            None => line,
+        };
+        let line: &str = match line.find(" --") {
+            Some(idx) => &line[..idx],
+            None => line,
+        };
+        let line: &str = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+            return None;
+        }
+        PipRequirement::from_string(line)
+    }
+}
+
+impl Default for PipRequirementsParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct PipPackageParser {
     packages_dir: WPath,
+    marker_environment: Option<PipMarkerEnvironment>,
 }
 
 impl PipPackageParser {
     pub fn new(packages_dir: &WPath) -> Self {
         let packages_dir = packages_dir.clone();
-        PipPackageParser { packages_dir }
+        PipPackageParser {
+            packages_dir,
+            marker_environment: None,
+        }
+    }
+
+    /// Environment markers are only evaluated once the interpreter version is known.
+    pub fn set_python_version(&mut self, python_version: &SemanticVersion) {
+        self.marker_environment = Some(PipMarkerEnvironment::new(python_version));
     }
 
     pub fn get_packages(&self) -> Result<Vec<PipPackage>, io::Error> {
@@ -411,10 +609,11 @@ impl PipPackageParser {
                 }
             }
         }
+        packages.sort_by(|a, b| a.get_name().cmp(b.get_name()));
         Ok(packages)
     }
 
-    pub fn get_metadata(&self, packages: &Vec<PipPackage>) -> Vec<PipMetadata> {
+    pub fn get_metadata(&self, packages: &[PipPackage]) -> Vec<PipMetadata> {
         let mut metadata: Vec<PipMetadata> = Vec::new();
 
         for package in packages {
@@ -423,11 +622,11 @@ impl PipPackageParser {
                 let file: Result<File, io::Error> = File::open(&metadata_path);
                 if let Ok(file) = file {
                     let reader: io::BufReader<File> = io::BufReader::new(file);
-                    let metadata_parser: PipMetadataParser = PipMetadataParser::new();
-                    let requires: HashMap<String, HashSet<PipPackageName>> =
-                        metadata_parser.parse_requires(reader);
+                    let metadata_parser: PipMetadataParser =
+                        PipMetadataParser::new(self.marker_environment.as_ref());
+                    let requirements: Vec<PipRequirement> = metadata_parser.parse_requires(reader);
 
-                    let pip_metadata: PipMetadata = PipMetadata::new(package, &requires);
+                    let pip_metadata: PipMetadata = PipMetadata::new(package, &requirements);
                     metadata.push(pip_metadata);
                 }
             }
@@ -436,57 +635,43 @@ impl PipPackageParser {
         metadata
     }
 
-    pub fn distill_packages(&self, packages: &mut Vec<PipPackage>, metadata: &Vec<PipMetadata>) {
-        for meta in metadata {
-            let requires: &HashMap<String, HashSet<PipPackageName>> = meta.get_requires();
-            let base: Option<&HashSet<PipPackageName>> = requires.get("base");
-
-            if let Some(base) = base {
-                self.distill_from_base_dependencies(base, packages);
+    /// Reduces `packages` to the smallest set of top-level requirements, including
+    /// extras, whose transitive closure reproduces the installed environment.
+    ///
+    /// Pre-releases are always kept pinned since pip would not resolve them implicitly.
+    pub fn distill_packages(&self, packages: &mut Vec<PipPackage>, metadata: &[PipMetadata]) {
+        let mut graph: PipDependencyGraph = PipDependencyGraph::new(packages, metadata);
+        let mut distilled: Vec<PipPackage> = graph.get_top_level();
+
+        for package in packages.iter() {
+            let is_distilled: bool = distilled
+                .iter()
+                .any(|distilled| distilled.get_name() == package.get_name());
+            if package.version.is_prerelease() && !is_distilled {
+                distilled.push(package.clone());
             }
         }
+
+        distilled.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+        *packages = distilled;
     }
 }
 
 impl PipPackageParser {
-    fn distill_from_base_dependencies(
-        &self,
-        base: &HashSet<PipPackageName>,
-        packages: &mut Vec<PipPackage>,
-    ) {
-        let mut to_remove: Vec<usize> = Vec::new();
-        for (idx, package) in packages.iter().enumerate() {
-            if !package.version.qualifier.is_empty() {
-                continue;
-            } else if base.contains(package.get_name()) {
-                to_remove.push(idx);
-            }
-        }
-
-        let mut shift = 0;
-        for idx in to_remove.iter() {
-            packages.remove(*idx - shift);
-            shift += 1
-        }
-    }
-
     fn parse_dir_name(
         &self,
         entry_path: &PathBuf,
         entry_name: &str,
         packages: &mut Vec<PipPackage>,
     ) {
-        if entry_name.ends_with(".dist-info") {
-            let string: &str = entry_name.trim_end_matches(".dist-info");
-            let parts: Vec<&str> = string.split("-").collect();
-            let name: PipPackageName = PipPackageName::new(parts[0]);
-            let version: Option<SemanticVersion> = SemanticVersion::from_string(parts[1]);
-
-            if let Some(version) = version {
-                let path: WPath = entry_path.into();
-                let package: PipPackage = PipPackage::new(name, version, path);
-                packages.push(package);
-            }
+        if let Some(string) = entry_name.strip_suffix(".dist-info") {
+            let (name, version): (&str, &str) = string.split_once('-').unwrap_or((string, ""));
+            let name: PipPackageName = PipPackageName::new(name);
+            let version: PipVersion = PipVersion::from_string_or_legacy(version);
+
+            let path: WPath = entry_path.into();
+            let package: PipPackage = PipPackage::new(name, version, path);
+            packages.push(package);
         }
     }
 }
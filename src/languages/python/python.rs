@@ -1,6 +1,7 @@
 use super::pip::Pip;
 
 use crate::misc::path::WPath;
+use crate::misc::shell::{CommandExecute, CommandResponse};
 use crate::misc::version::SemanticVersion;
 use crate::terminal::RedANSI;
 use crate::terminal::Terminal;
@@ -19,7 +20,7 @@ impl PythonEnvironment {
         let python_path: Option<WPath> = Self::get_python_path(&base_dir, &version);
 
         if let Some(python_path) = python_path {
-            let python_executable = python_path.join("python.exe");
+            let python_executable: WPath = Self::get_executable_path(&python_path, version);
             let pip: Option<Pip> = Pip::new(&python_executable);
             if let Some(pip) = pip {
                 let python_version: SemanticVersion = version.clone();
@@ -64,6 +65,27 @@ impl PythonEnvironment {
         None
     }
 
+    /// Directory of a Python installation, `<base_dir>/PythonXY`. Windows installers and
+    /// `python install` both install there.
+    pub fn get_install_path(base_dir: &WPath, version: &SemanticVersion) -> WPath {
+        let folder_name: String = format!("Python{}{}", version.major, version.minor);
+        base_dir.join(folder_name)
+    }
+
+    pub fn get_executable_version(python_executable: &WPath) -> Option<SemanticVersion> {
+        let args: [&str; 1] = ["--version"];
+        let command: CommandExecute = CommandExecute::new();
+        let response: CommandResponse = command.execute_command(python_executable, &args)?;
+
+        let mut output: &str = response.get_stdout();
+        if output.is_empty() {
+            output = response.get_stderr();
+        }
+
+        let version_string: &str = output.trim().trim_start_matches("Python").trim();
+        SemanticVersion::from_string(version_string)
+    }
+
     pub fn get_python_executable(&self) -> &WPath {
         &self.python_executable
     }
@@ -79,12 +101,21 @@ impl PythonEnvironment {
 
 impl PythonEnvironment {
     fn get_python_path(base_path: &WPath, version: &SemanticVersion) -> Option<WPath> {
-        let folder_name: String = format!("Python{}{}\\", version.major, version.minor);
-        let folder_path: &WPath = &WPath::from_string(&folder_name);
-        let python_path: WPath = base_path.join(folder_path);
+        let python_path: WPath = Self::get_install_path(base_path, version);
         if python_path.exists() {
             return Some(python_path);
         }
         None
     }
+
+    /// Windows installers place `python.exe` in the install directory, while source
+    /// builds from `make altinstall` place `bin/pythonX.Y` under their prefix.
+    fn get_executable_path(python_path: &WPath, version: &SemanticVersion) -> WPath {
+        let windows_executable: WPath = python_path.join("python.exe");
+        if windows_executable.exists() {
+            return windows_executable;
+        }
+        let executable_name: String = format!("python{}", version.get_2p_string());
+        python_path.join("bin").join(executable_name)
+    }
 }
@@ -0,0 +1,299 @@
+use std::fs;
+use std::fs::{DirEntry, FileType, ReadDir};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::virtualenv::VirtualEnvCFG;
+
+use crate::misc::path::WPath;
+use crate::misc::shell::{CommandExecute, CommandResponse};
+
+use crate::terminal::Terminal;
+use crate::terminal::{CyanANSI, GreenANSI, YellowANSI};
+
+pub struct VirtualEnvRelocator {
+    source: WPath,
+    destination: WPath,
+    terminal: Terminal,
+}
+
+impl VirtualEnvRelocator {
+    pub fn new(source: &WPath, destination: &WPath) -> Self {
+        let source: WPath = source.clone();
+        let destination: WPath = destination.clone();
+        let terminal: Terminal = Terminal::new();
+        VirtualEnvRelocator {
+            source,
+            destination,
+            terminal,
+        }
+    }
+
+    /// Moves the environment and rewrites its paths. If any step after the move fails,
+    /// the rewritten files are restored and the environment is moved back.
+    pub fn relocate(&self) -> Result<(), io::Error> {
+        let source_string: String = self.get_source_string()?;
+        self.validate_destination()?;
+
+        let string: &str = "[Moving Environment]";
+        self.terminal.writeln_ansi(string, &CyanANSI);
+        self.move_environment(&self.source, &self.destination)?;
+
+        let mut originals: Vec<(WPath, Vec<u8>)> = Vec::new();
+        let result: Result<(), io::Error> = self.relocate_paths(&source_string, &mut originals);
+
+        if let Err(error) = result {
+            let string: &str = "[Restoring Environment]";
+            self.terminal.writeln_ansi(string, &YellowANSI);
+            if let Err(restore_error) = self.restore(&originals) {
+                let error_string: String = format!(
+                    "{}, and restoring failed: {}. The environment was left in {:?}",
+                    error,
+                    restore_error,
+                    self.destination.get_path_buf()
+                );
+                return Err(io::Error::new(error.kind(), error_string));
+            }
+            return Err(error);
+        }
+        Ok(())
+    }
+}
+
+impl VirtualEnvRelocator {
+    /// Resolves the source path once its pyvenv.cfg is known to parse, so nothing is
+    /// moved for an environment that cannot be rewritten.
+    fn get_source_string(&self) -> Result<String, io::Error> {
+        let cfg_file: WPath = self.source.join("pyvenv.cfg");
+        if !cfg_file.exists() {
+            let error_string: String = format!("No pyvenv.cfg found in {:?}", self.source);
+            return Err(io::Error::new(io::ErrorKind::NotFound, error_string));
+        }
+        if VirtualEnvCFG::from_file(&cfg_file).is_none() {
+            let error_string: String = format!("Unable to parse {:?}", cfg_file);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error_string));
+        }
+        self.get_path_string(&self.source)
+    }
+
+    fn get_destination_string(&self) -> Result<String, io::Error> {
+        self.get_path_string(&self.destination)
+    }
+
+    fn get_path_string(&self, path: &WPath) -> Result<String, io::Error> {
+        let path_string: Option<String> = path.get_canonical_string();
+        if let Some(path_string) = path_string {
+            return Ok(path_string);
+        }
+        let error_string: String = format!("Unable to resolve path: {:?}", path.get_path_buf());
+        Err(io::Error::new(io::ErrorKind::InvalidInput, error_string))
+    }
+
+    fn validate_destination(&self) -> Result<(), io::Error> {
+        if self.destination.exists() {
+            let error_string: String = format!(
+                "Destination already exists: {:?}",
+                self.destination.get_path_buf()
+            );
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, error_string));
+        }
+        Ok(())
+    }
+
+    /// Rewrites every path of the moved environment, recording the original content of
+    /// each rewritten file in `originals`.
+    fn relocate_paths(
+        &self,
+        source: &str,
+        originals: &mut Vec<(WPath, Vec<u8>)>,
+    ) -> Result<(), io::Error> {
+        let destination: String = self.get_destination_string()?;
+
+        self.terminal.writeln_ansi("[Rewriting Paths]", &CyanANSI);
+        let venv_cfg: VirtualEnvCFG = self.rewrite_cfg(source, &destination, originals)?;
+        self.rewrite_scripts(&venv_cfg, source, &destination, originals)?;
+        self.rewrite_pth_files(&venv_cfg, source, &destination, originals)?;
+
+        let string: &str = "[Verifying Environment]";
+        self.terminal.writeln_ansi(string, &CyanANSI);
+        self.verify(&venv_cfg, &destination)
+    }
+
+    /// Writes back the original content of rewritten files and moves the environment
+    /// to its source path.
+    fn restore(&self, originals: &[(WPath, Vec<u8>)]) -> Result<(), io::Error> {
+        for (path, content) in originals.iter().rev() {
+            fs::write(path, content)?;
+        }
+        self.move_environment(&self.destination, &self.source)?;
+
+        let source_string: String = format!("{:?}", self.source.get_path_buf());
+        let parts: [&str; 2] = ["Restored Environment: ", &source_string];
+        self.terminal.writeln_parameter(&parts, &YellowANSI);
+        Ok(())
+    }
+
+    fn move_environment(&self, source: &WPath, destination: &WPath) -> Result<(), io::Error> {
+        if let Err(error) = fs::rename(source, destination) {
+            if error.kind() == io::ErrorKind::NotFound {
+                return Err(error);
+            }
+
+            // Renaming fails across devices, fall back to copying the tree.
+            self.copy_directory(source.as_ref(), destination.as_ref())?;
+            fs::remove_dir_all(source)?;
+        }
+        Ok(())
+    }
+
+    fn copy_directory(&self, source: &Path, destination: &Path) -> Result<(), io::Error> {
+        fs::create_dir_all(destination)?;
+        for entry in source.read_dir()? {
+            let entry: DirEntry = entry?;
+            let file_type: FileType = entry.file_type()?;
+            let target: PathBuf = destination.join(entry.file_name());
+
+            if file_type.is_dir() {
+                self.copy_directory(&entry.path(), &target)?;
+            } else if file_type.is_symlink() {
+                self.copy_symlink(&entry.path(), &target)?;
+            } else {
+                fs::copy(entry.path(), &target)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn copy_symlink(&self, source: &Path, destination: &Path) -> Result<(), io::Error> {
+        let link: PathBuf = fs::read_link(source)?;
+        std::os::unix::fs::symlink(link, destination)
+    }
+
+    #[cfg(not(unix))]
+    fn copy_symlink(&self, source: &Path, destination: &Path) -> Result<(), io::Error> {
+        fs::copy(source, destination)?;
+        Ok(())
+    }
+
+    fn rewrite_cfg(
+        &self,
+        source: &str,
+        destination: &str,
+        originals: &mut Vec<(WPath, Vec<u8>)>,
+    ) -> Result<VirtualEnvCFG, io::Error> {
+        let cfg_file: WPath = self.destination.join("pyvenv.cfg");
+        let venv_cfg: Option<VirtualEnvCFG> = VirtualEnvCFG::from_file(&cfg_file);
+
+        if let Some(mut venv_cfg) = venv_cfg {
+            if venv_cfg.relocate(source, destination) {
+                originals.push((cfg_file.clone(), fs::read(&cfg_file)?));
+                venv_cfg.save()?;
+                self.print_rewritten(&cfg_file);
+            }
+            return Ok(venv_cfg);
+        }
+
+        let error_string: String = format!("Unable to parse {:?}", cfg_file);
+        Err(io::Error::new(io::ErrorKind::InvalidData, error_string))
+    }
+
+    fn rewrite_scripts(
+        &self,
+        venv_cfg: &VirtualEnvCFG,
+        source: &str,
+        destination: &str,
+        originals: &mut Vec<(WPath, Vec<u8>)>,
+    ) -> Result<(), io::Error> {
+        let scripts_directory: WPath = venv_cfg.get_scripts_directory();
+        let read_dir: ReadDir = scripts_directory.read_dir()?;
+
+        for entry in read_dir {
+            let entry: DirEntry = entry?;
+            let file_type: FileType = entry.file_type()?;
+
+            // Interpreter symlinks point at the base installation and must be left alone.
+            if file_type.is_file() {
+                let path: WPath = WPath::from_path_buf(&entry.path());
+                self.rewrite_file(&path, source, destination, originals)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn rewrite_pth_files(
+        &self,
+        venv_cfg: &VirtualEnvCFG,
+        source: &str,
+        destination: &str,
+        originals: &mut Vec<(WPath, Vec<u8>)>,
+    ) -> Result<(), io::Error> {
+        let site_packages: WPath = venv_cfg.get_site_packages_directory();
+        let read_dir: Result<ReadDir, io::Error> = site_packages.read_dir();
+
+        if let Ok(read_dir) = read_dir {
+            for entry in read_dir.flatten() {
+                let path: PathBuf = entry.path();
+                let is_pth: bool = path.extension().is_some_and(|ext| ext == "pth");
+                if is_pth && path.is_file() {
+                    let path: WPath = WPath::from_path_buf(&path);
+                    self.rewrite_file(&path, source, destination, originals)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rewrite_file(
+        &self,
+        path: &WPath,
+        source: &str,
+        destination: &str,
+        originals: &mut Vec<(WPath, Vec<u8>)>,
+    ) -> Result<(), io::Error> {
+        let content: Vec<u8> = fs::read(path)?;
+        let replaced: Option<Vec<u8>> =
+            WPath::replace_path_bytes(&content, source.as_bytes(), destination.as_bytes());
+
+        if let Some(replaced) = replaced {
+            originals.push((path.clone(), content));
+            fs::write(path, replaced)?;
+            self.print_rewritten(path);
+        }
+        Ok(())
+    }
+
+    fn verify(&self, venv_cfg: &VirtualEnvCFG, destination: &str) -> Result<(), io::Error> {
+        let python_executable: WPath = venv_cfg.get_python_executable();
+        let args: [&str; 2] = ["-c", "import sys; print(sys.prefix)"];
+        let command: CommandExecute = CommandExecute::new();
+        let response: Option<CommandResponse> = command.execute_command(&python_executable, &args);
+
+        if let Some(response) = response {
+            let prefix: WPath = WPath::from_string(response.get_stdout());
+            let prefix_string: Option<String> = prefix.get_canonical_string();
+
+            if prefix_string.as_deref() == Some(destination) {
+                let parts: [&str; 2] = ["Relocated Environment: ", destination];
+                self.terminal.writeln_parameter(&parts, &GreenANSI);
+                return Ok(());
+            }
+
+            response.print();
+            let error_string: String = format!(
+                "Relocated interpreter reports an unexpected prefix: {}",
+                response.get_stdout()
+            );
+            return Err(io::Error::other(error_string));
+        }
+
+        let error_string: String = format!("Unable to execute {:?}", python_executable);
+        Err(io::Error::other(error_string))
+    }
+
+    fn print_rewritten(&self, path: &WPath) {
+        let path_string: String = format!("[{:?}]", path);
+        let parts: [&str; 2] = ["Rewritten: ", &path_string];
+        self.terminal.writeln_parameter(&parts, &GreenANSI);
+    }
+}
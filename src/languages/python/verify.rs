@@ -0,0 +1,371 @@
+use std::error::Error;
+use std::io;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::Value;
+
+use crate::misc::checksum::Sha256Checksum;
+use crate::misc::https::{HTTPSConfig, HTTPS};
+use crate::misc::path::WPath;
+
+pub enum PythonVerification {
+    Verified(String),
+    Mismatch { expected: String, actual: String },
+    Unavailable,
+}
+
+/// Verifies a downloaded artifact against the sidecar files published next to it.
+///
+/// The SHA-256 digest is taken from a `.sha256` file when a mirror provides one and
+/// from the message digest of the `.sigstore` bundle otherwise. `.sig` and `.asc`
+/// signatures are only reported since checking them needs the release keys.
+pub struct PythonArtifactVerifier {
+    sidecar_urls: Vec<String>,
+    checksum: Sha256Checksum,
+    https_config: HTTPSConfig,
+}
+
+impl PythonArtifactVerifier {
+    pub fn new(sidecar_urls: &[String]) -> Self {
+        let sidecar_urls: Vec<String> = sidecar_urls.to_vec();
+        let checksum: Sha256Checksum = Sha256Checksum::new();
+        let https_config: HTTPSConfig = HTTPSConfig::new();
+        PythonArtifactVerifier {
+            sidecar_urls,
+            checksum,
+            https_config,
+        }
+    }
+
+    /// Sets the timeouts, retries and proxies used to fetch the sidecar files.
+    pub fn set_https_config(&mut self, https_config: HTTPSConfig) {
+        self.https_config = https_config;
+    }
+
+    pub fn get_sidecar_urls(&self) -> &Vec<String> {
+        &self.sidecar_urls
+    }
+
+    pub fn get_signature_urls(&self) -> Vec<&str> {
+        self.sidecar_urls
+            .iter()
+            .filter(|url| url.ends_with(".sig") || url.ends_with(".asc"))
+            .map(|url| url.as_str())
+            .collect()
+    }
+
+    /// Fetches the expected SHA-256 digest from the sidecar files, if any publish one.
+    /// A sidecar that cannot be fetched or parsed is skipped for the next one, and its
+    /// error is only returned when no other sidecar provides a digest.
+    pub async fn get_expected_digest(&self) -> Result<Option<String>, Box<dyn Error>> {
+        let https: HTTPS = HTTPS::from_config(self.https_config.clone());
+        let mut first_error: Option<Box<dyn Error>> = None;
+
+        for url in self.sidecar_urls.iter() {
+            let digest: Result<Option<String>, Box<dyn Error>> =
+                self.get_sidecar_digest(&https, url).await;
+
+            match digest {
+                Ok(Some(digest)) => return Ok(Some(digest)),
+                Ok(None) => {}
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn verify(&self, file_path: &WPath) -> Result<PythonVerification, Box<dyn Error>> {
+        let expected: Option<String> = self.get_expected_digest().await?;
+        let verification: PythonVerification =
+            self.verify_against(file_path, expected.as_deref())?;
+        Ok(verification)
+    }
+
+    /// Compares a file with a digest fetched earlier, so several files can be checked
+    /// without downloading the sidecar files again.
+    pub fn verify_against(
+        &self,
+        file_path: &WPath,
+        expected: Option<&str>,
+    ) -> Result<PythonVerification, io::Error> {
+        let expected: String = match expected {
+            Some(expected) => expected.to_string(),
+            None => return Ok(PythonVerification::Unavailable),
+        };
+
+        let actual: String = self.checksum.get_file_digest(file_path)?;
+        if actual.eq_ignore_ascii_case(&expected) {
+            return Ok(PythonVerification::Verified(actual));
+        }
+        Ok(PythonVerification::Mismatch { expected, actual })
+    }
+}
+
+impl PythonArtifactVerifier {
+    async fn get_sidecar_digest(
+        &self,
+        https: &HTTPS,
+        url: &str,
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        if url.ends_with(".sha256") {
+            let body: String = https.get_response_body(url).await?;
+            return Ok(self.parse_sha256_file(&body));
+        }
+        if url.ends_with(".sigstore") {
+            let body: String = https.get_response_body(url).await?;
+            return Ok(Some(self.parse_sigstore_bundle(&body)?));
+        }
+        Ok(None)
+    }
+
+    /// Accepts either a bare digest or `sha256sum` output (`<digest>  <file>`).
+    fn parse_sha256_file(&self, body: &str) -> Option<String> {
+        let digest: &str = body.split_whitespace().next()?;
+        if self.checksum.is_digest(digest) {
+            return Some(digest.to_lowercase());
+        }
+        None
+    }
+
+    fn parse_sigstore_bundle(&self, body: &str) -> Result<String, io::Error> {
+        let bundle: Value = serde_json::from_str(body)?;
+        let message_digest: Option<&Value> = bundle
+            .get("messageSignature")
+            .and_then(|signature| signature.get("messageDigest"));
+
+        let algorithm: Option<&str> = message_digest
+            .and_then(|message_digest| message_digest.get("algorithm"))
+            .and_then(|algorithm| algorithm.as_str());
+        let digest: Option<&str> = message_digest
+            .and_then(|message_digest| message_digest.get("digest"))
+            .and_then(|digest| digest.as_str());
+
+        match (algorithm, digest) {
+            (Some("SHA2_256"), Some(digest)) => {
+                let bytes: Vec<u8> = STANDARD
+                    .decode(digest)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+                Ok(self.checksum.to_hex(&bytes))
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Sigstore bundle does not contain a SHA-256 message digest",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::mpsc::Receiver;
+
+    use super::*;
+    use crate::languages::python::ftp::PythonFTPRetriever;
+    use crate::misc::test_utils;
+
+    const ARTIFACT: &[u8] = b"python installer";
+    const ARTIFACT_NAME: &str = "python-3.12.0-amd64.exe";
+
+    fn get_verifier() -> PythonArtifactVerifier {
+        PythonArtifactVerifier::new(&[])
+    }
+
+    fn get_artifact_digest() -> String {
+        Sha256Checksum::new().get_bytes_digest(ARTIFACT)
+    }
+
+    fn get_sigstore_bundle(algorithm: &str) -> String {
+        let digest: Vec<u8> = (0..get_artifact_digest().len())
+            .step_by(2)
+            .map(|idx| u8::from_str_radix(&get_artifact_digest()[idx..idx + 2], 16).unwrap())
+            .collect();
+        format!(
+            r#"{{"messageSignature": {{"messageDigest": {{"algorithm": "{}", "digest": "{}"}}}}}}"#,
+            algorithm,
+            STANDARD.encode(digest)
+        )
+    }
+
+    fn get_temp_path(name: &str) -> PathBuf {
+        test_utils::get_temp_path("verify", name)
+    }
+
+    fn write_temp_file(name: &str, content: &[u8]) -> PathBuf {
+        let path: PathBuf = get_temp_path(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Serves `(path, content type, body)` routes, answering 404 for anything else, and
+    /// returns the base URL.
+    fn serve(routes: Vec<(String, &'static str, Vec<u8>)>) -> String {
+        let (base_url, _requests): (String, Receiver<String>) = test_utils::serve(move |request| {
+            let path: &str = test_utils::get_request_path(request);
+            let route: Option<&(String, &str, Vec<u8>)> =
+                routes.iter().find(|(route, _, _)| route == path);
+            let (status, content_type, body): (&str, &str, &[u8]) = match route {
+                Some((_, content_type, body)) => ("200 OK", content_type, body),
+                None => ("404 Not Found", "text/plain", b"not found"),
+            };
+            let content_type: String = format!("Content-Type: {}", content_type);
+            test_utils::get_response(status, &[&content_type], body, body.len())
+        });
+        base_url
+    }
+
+    fn get_verifier_for(sidecar_urls: &[String]) -> PythonArtifactVerifier {
+        let mut verifier: PythonArtifactVerifier = PythonArtifactVerifier::new(sidecar_urls);
+        verifier.set_https_config(test_utils::get_https_config());
+        verifier
+    }
+
+    fn get_listing(files: &[&str]) -> Vec<u8> {
+        let links: Vec<String> = files
+            .iter()
+            .map(|file| format!(r#"<a href="{}">{}</a>"#, file, file))
+            .collect();
+        format!("<html><body>{}</body></html>", links.join("\n")).into_bytes()
+    }
+
+    #[test]
+    fn parses_bare_sha256_digest() {
+        let digest: String = get_artifact_digest();
+        let body: String = format!("{}\n", digest.to_uppercase());
+        assert_eq!(get_verifier().parse_sha256_file(&body), Some(digest));
+    }
+
+    #[test]
+    fn parses_sha256sum_output() {
+        let digest: String = get_artifact_digest();
+        let body: String = format!("{}  {}\n", digest, ARTIFACT_NAME);
+        assert_eq!(get_verifier().parse_sha256_file(&body), Some(digest));
+    }
+
+    #[test]
+    fn rejects_invalid_sha256_file() {
+        assert_eq!(get_verifier().parse_sha256_file("not-a-digest  file"), None);
+        assert_eq!(get_verifier().parse_sha256_file(""), None);
+    }
+
+    #[test]
+    fn parses_sigstore_sha256_digest() {
+        let bundle: String = get_sigstore_bundle("SHA2_256");
+        let digest: String = get_verifier().parse_sigstore_bundle(&bundle).unwrap();
+        assert_eq!(digest, get_artifact_digest());
+    }
+
+    #[test]
+    fn rejects_sigstore_with_other_algorithm() {
+        let bundle: String = get_sigstore_bundle("SHA2_384");
+        assert!(get_verifier().parse_sigstore_bundle(&bundle).is_err());
+    }
+
+    #[test]
+    fn verifies_matching_file() {
+        let path: PathBuf = write_temp_file("match", ARTIFACT);
+        let file_path: WPath = WPath::from_path_buf(&path);
+        let digest: String = get_artifact_digest();
+        let verification: PythonVerification = get_verifier()
+            .verify_against(&file_path, Some(&digest))
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(verification, PythonVerification::Verified(actual) if actual == digest));
+    }
+
+    #[test]
+    fn detects_mismatching_file() {
+        let path: PathBuf = write_temp_file("mismatch", b"tampered installer");
+        let file_path: WPath = WPath::from_path_buf(&path);
+        let digest: String = get_artifact_digest();
+        let verification: PythonVerification = get_verifier()
+            .verify_against(&file_path, Some(&digest))
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            verification,
+            PythonVerification::Mismatch { expected, actual } if expected == digest && actual != digest
+        ));
+    }
+
+    #[test]
+    fn reports_unavailable_digest() {
+        let path: PathBuf = write_temp_file("unavailable", ARTIFACT);
+        let file_path: WPath = WPath::from_path_buf(&path);
+        let verification: PythonVerification =
+            get_verifier().verify_against(&file_path, None).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(verification, PythonVerification::Unavailable));
+    }
+
+    #[tokio::test]
+    async fn verifies_download_from_local_server() {
+        let sha256_name: String = format!("{}.sha256", ARTIFACT_NAME);
+        let sha256_body: String = format!("{}  {}\n", get_artifact_digest(), ARTIFACT_NAME);
+        let base_url: String = serve(vec![
+            (
+                "/3.12.0/".to_string(),
+                "text/html",
+                get_listing(&[ARTIFACT_NAME, &sha256_name]),
+            ),
+            (
+                format!("/3.12.0/{}", ARTIFACT_NAME),
+                "application/octet-stream",
+                ARTIFACT.to_vec(),
+            ),
+            (
+                format!("/3.12.0/{}", sha256_name),
+                "text/plain",
+                sha256_body.into_bytes(),
+            ),
+        ]);
+
+        let url: String = format!("{}3.12.0/{}", base_url, ARTIFACT_NAME);
+        let mut ftp_retriever: PythonFTPRetriever = PythonFTPRetriever::from_url(&base_url);
+        ftp_retriever.set_https_config(test_utils::get_https_config());
+        let sidecar_urls: Vec<String> = ftp_retriever.get_sidecar_urls(&url).await;
+        assert_eq!(sidecar_urls, vec![format!("{}.sha256", url)]);
+
+        let download_path: PathBuf = get_temp_path("download");
+        HTTPS::from_config(test_utils::get_https_config())
+            .download_to(&url, &download_path)
+            .await
+            .unwrap();
+        assert_eq!(fs::read(&download_path).unwrap(), ARTIFACT);
+
+        let verifier: PythonArtifactVerifier = get_verifier_for(&sidecar_urls);
+        let file_path: WPath = WPath::from_path_buf(&download_path);
+        let verification: PythonVerification = verifier.verify(&file_path).await.unwrap();
+        fs::remove_file(&download_path).unwrap();
+        assert!(matches!(
+            verification,
+            PythonVerification::Verified(actual) if actual == get_artifact_digest()
+        ));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_sigstore_when_sha256_fails() {
+        let base_url: String = serve(vec![(
+            format!("/{}.sigstore", ARTIFACT_NAME),
+            "application/json",
+            get_sigstore_bundle("SHA2_256").into_bytes(),
+        )]);
+
+        // The `.sha256` sidecar is listed but answers 404.
+        let sidecar_urls: Vec<String> = vec![
+            format!("{}{}.sha256", base_url, ARTIFACT_NAME),
+            format!("{}{}.sigstore", base_url, ARTIFACT_NAME),
+        ];
+        let verifier: PythonArtifactVerifier = get_verifier_for(&sidecar_urls);
+        let digest: Option<String> = verifier.get_expected_digest().await.unwrap();
+        assert_eq!(digest, Some(get_artifact_digest()));
+    }
+}
@@ -1,9 +1,11 @@
 use std::collections::HashSet;
+use std::fs;
 use std::io;
 use std::path::PathBuf;
+use std::process::ExitStatus;
 use std::time::Duration;
 
-use super::pip::{Pip, PipShow};
+use super::pip::{Pip, PipPackageName, PipShow};
 use super::python::PythonEnvironment;
 
 use crate::misc::path::WPath;
@@ -11,40 +13,70 @@ use crate::misc::shell::{CommandExecute, CommandResponse};
 use crate::misc::version::SemanticVersion;
 use crate::parsers::cfg_parser::CFGLine;
 use crate::parsers::cfg_parser::CFGParser;
+use crate::parsers::glob_parser::GlobPattern;
 use crate::search::file_info::FileInfo;
 use crate::search::file_search::{FileSearch, SearchThreadScheduler};
 
 use crate::terminal::Terminal;
-use crate::terminal::{CyanANSI, GreenANSI, RedANSI};
+use crate::terminal::{CyanANSI, GreenANSI, RedANSI, YellowANSI};
 
-use crate::utils::ConfirmationPrompt;
+use crate::utils::{ConfirmationPrompt, SelectionPrompt};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+pub enum VirtualEnvCreator {
+    Virtualenv(SemanticVersion),
+    Uv(SemanticVersion),
+    Venv,
+    Unknown,
+}
+
+impl VirtualEnvCreator {
+    pub fn get_string(&self) -> String {
+        match self {
+            VirtualEnvCreator::Virtualenv(version) => {
+                format!("virtualenv {}", version.get_string())
+            }
+            VirtualEnvCreator::Uv(version) => format!("uv {}", version.get_string()),
+            VirtualEnvCreator::Venv => "venv".to_string(),
+            VirtualEnvCreator::Unknown => "unknown".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct VirtualEnvCFG {
-    pub home: WPath,
-    pub implementation: String,
-    pub version_info: SemanticVersion,
-    pub virtualenv: SemanticVersion,
+    pub home: Option<WPath>,
+    pub implementation: Option<String>,
+    pub version_info: Option<SemanticVersion>,
+    pub virtualenv: Option<SemanticVersion>,
+    pub uv: Option<SemanticVersion>,
     pub include_system_site_packages: bool,
-    pub base_prefix: WPath,
-    pub base_exec_prefix: WPath,
-    pub base_executable: WPath,
+    pub base_prefix: Option<WPath>,
+    pub base_exec_prefix: Option<WPath>,
+    pub base_executable: Option<WPath>,
+    pub prompt: Option<String>,
+    pub command: Option<String>,
     pub cfg_file: WPath,
+    cfg_lines: Vec<CFGLine>,
 }
 
 impl VirtualEnvCFG {
-    pub fn new(cfg_file: WPath, parsed_cfg: &Vec<CFGLine>) -> Option<Self> {
+    pub fn new(cfg_file: WPath, parsed_cfg: &[CFGLine]) -> Option<Self> {
         let mut home: Option<WPath> = None;
         let mut implementation: Option<String> = None;
         let mut version_info: Option<SemanticVersion> = None;
         let mut virtualenv: Option<SemanticVersion> = None;
-        let mut include_system_site_packages: Option<bool> = None;
+        let mut uv: Option<SemanticVersion> = None;
+        let mut include_system_site_packages: bool = false;
         let mut base_prefix: Option<WPath> = None;
         let mut base_exec_prefix: Option<WPath> = None;
         let mut base_executable: Option<WPath> = None;
+        let mut executable: Option<WPath> = None;
+        let mut prompt: Option<String> = None;
+        let mut command: Option<String> = None;
 
         for cfg_line in parsed_cfg {
-            let cfg_name: String = cfg_line.get_name().to_string();
+            let cfg_name: String = cfg_line.get_name().to_lowercase();
             let cfg_setting: String = cfg_line.get_setting().to_string();
             let cfg_path: WPath = WPath::from_string(&cfg_setting);
             let cfg_version: Option<SemanticVersion> = SemanticVersion::from_string(&cfg_setting);
@@ -53,45 +85,174 @@ impl VirtualEnvCFG {
             match cfg_name.as_ref() {
                 "home" => home = Some(cfg_path),
                 "implementation" => implementation = Some(cfg_setting),
-                "version_info" => version_info = Some(cfg_version?),
-                "virtualenv" => virtualenv = Some(cfg_version?),
-                "include-system-site-packages" => include_system_site_packages = Some(cfg_boolean?),
+                "version_info" => version_info = cfg_version.or(version_info),
+                "version" => version_info = version_info.or(cfg_version),
+                "virtualenv" => virtualenv = cfg_version,
+                "uv" => uv = cfg_version,
+                "include-system-site-packages" => {
+                    include_system_site_packages = cfg_boolean.unwrap_or(false)
+                }
                 "base-prefix" => base_prefix = Some(cfg_path),
                 "base-exec-prefix" => base_exec_prefix = Some(cfg_path),
                 "base-executable" => base_executable = Some(cfg_path),
-                _ => return None,
+                "executable" => executable = Some(cfg_path),
+                "prompt" => prompt = Some(cfg_setting),
+                "command" => command = Some(cfg_setting),
+                _ => {}
             }
         }
 
+        if parsed_cfg.is_empty() {
+            return None;
+        }
+
         let venv_cfg: VirtualEnvCFG = VirtualEnvCFG {
-            home: home.unwrap(),
-            implementation: implementation.unwrap(),
-            version_info: version_info.unwrap(),
-            virtualenv: virtualenv.unwrap(),
-            include_system_site_packages: include_system_site_packages.unwrap(),
-            base_prefix: base_prefix.unwrap(),
-            base_exec_prefix: base_exec_prefix.unwrap(),
-            base_executable: base_executable.unwrap(),
+            home,
+            implementation,
+            version_info,
+            virtualenv,
+            uv,
+            include_system_site_packages,
+            base_prefix,
+            base_exec_prefix,
+            base_executable: base_executable.or(executable),
+            prompt,
+            command,
             cfg_file,
+            cfg_lines: parsed_cfg.to_vec(),
         };
         Some(venv_cfg)
     }
 
+    pub fn from_file(cfg_file: &WPath) -> Option<Self> {
+        let cfg_parser: CFGParser = CFGParser::new();
+        let cfg_path: &PathBuf = cfg_file.get_path_buf();
+        let parsed_cfg: Vec<CFGLine> = cfg_parser.from_file(cfg_path).ok()?;
+        VirtualEnvCFG::new(cfg_file.clone(), &parsed_cfg)
+    }
+
+    pub fn relocate(&mut self, source: &str, destination: &str) -> bool {
+        let mut relocated: bool = false;
+        for cfg_line in self.cfg_lines.iter_mut() {
+            let setting: &[u8] = cfg_line.get_setting().as_bytes();
+            let replaced: Option<Vec<u8>> =
+                WPath::replace_path_bytes(setting, source.as_bytes(), destination.as_bytes());
+            if let Some(replaced) = replaced {
+                let setting: String = String::from_utf8_lossy(&replaced).to_string();
+                cfg_line.set_setting(setting);
+                relocated = true;
+            }
+        }
+
+        if relocated {
+            let cfg_file: WPath = self.cfg_file.clone();
+            let venv_cfg: Option<VirtualEnvCFG> = VirtualEnvCFG::new(cfg_file, &self.cfg_lines);
+            if let Some(venv_cfg) = venv_cfg {
+                *self = venv_cfg;
+            }
+        }
+        relocated
+    }
+
+    pub fn get_setting(&self, name: &str) -> Option<&str> {
+        for cfg_line in &self.cfg_lines {
+            if cfg_line.get_name().eq_ignore_ascii_case(name) {
+                return Some(cfg_line.get_setting());
+            }
+        }
+        None
+    }
+
+    pub fn get_cfg_lines(&self) -> &Vec<CFGLine> {
+        &self.cfg_lines
+    }
+
+    pub fn get_creator(&self) -> VirtualEnvCreator {
+        if let Some(virtualenv) = &self.virtualenv {
+            return VirtualEnvCreator::Virtualenv(virtualenv.clone());
+        }
+        if let Some(uv) = &self.uv {
+            return VirtualEnvCreator::Uv(uv.clone());
+        }
+        if self.home.is_some() {
+            return VirtualEnvCreator::Venv;
+        }
+        VirtualEnvCreator::Unknown
+    }
+
+    pub fn get_version(&self) -> Option<SemanticVersion> {
+        if let Some(version_info) = &self.version_info {
+            return Some(version_info.clone());
+        }
+        let python_executable: WPath = self.get_python_executable();
+        PythonEnvironment::get_executable_version(&python_executable)
+    }
+
+    pub fn save(&self) -> Result<(), io::Error> {
+        let cfg_parser: CFGParser = CFGParser::new();
+        let content: String = cfg_parser.to_content(&self.cfg_lines);
+        fs::write(&self.cfg_file, content)
+    }
+
     pub fn get_environment_directory(&self) -> WPath {
         let directory: WPath = self.cfg_file.as_directory();
         directory
     }
 
-    pub fn get_python_executable(&self) -> WPath {
+    pub fn get_scripts_directory(&self) -> WPath {
         let directory: WPath = self.get_environment_directory();
-        let python_executable: WPath = directory.join("Scripts/python.exe");
+        let scripts_directory: WPath = if cfg!(windows) {
+            directory.join("Scripts")
+        } else {
+            directory.join("bin")
+        };
+        scripts_directory
+    }
+
+    pub fn get_site_packages_directory(&self) -> WPath {
+        let directory: WPath = self.get_environment_directory();
+        let site_packages: WPath = if cfg!(windows) {
+            directory.join("Lib/site-packages")
+        } else {
+            let lib_directory: WPath = directory.join("lib");
+            let python_directory: Option<String> = self.find_python_lib_directory(&lib_directory);
+            let python_directory: String = python_directory.unwrap_or_else(|| "python".to_string());
+            lib_directory.join(python_directory).join("site-packages")
+        };
+        site_packages
+    }
+
+    pub fn get_python_executable(&self) -> WPath {
+        let scripts_directory: WPath = self.get_scripts_directory();
+        let python_executable: WPath = if cfg!(windows) {
+            scripts_directory.join("python.exe")
+        } else {
+            scripts_directory.join("python")
+        };
         python_executable
     }
 }
 
 impl VirtualEnvCFG {
+    fn find_python_lib_directory(&self, lib_directory: &WPath) -> Option<String> {
+        if let Some(version_info) = &self.version_info {
+            let directory: String = format!("python{}", version_info.get_2p_string());
+            if lib_directory.join(&directory).exists() {
+                return Some(directory);
+            }
+        }
+
+        for entry in lib_directory.read_dir().ok()?.flatten() {
+            let name: String = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with("python") && entry.path().is_dir() {
+                return Some(name);
+            }
+        }
+        None
+    }
+
     fn parse_boolean_string(boolean: &str) -> Option<bool> {
-        match boolean {
+        match boolean.to_lowercase().as_ref() {
             "true" => Some(true),
             "false" => Some(false),
             _ => None,
@@ -130,11 +291,17 @@ impl VirtualEnv {
         }
     }
 
-    pub fn execute_custom_command(&self, args: &[&str]) {
+    pub fn execute_custom_command(&self, args: &[&str]) -> Option<ExitStatus> {
         let python_executable: &WPath = self.environment.get_python_executable();
         self.print_executing_custom_command();
         let command: CommandExecute = CommandExecute::new();
-        command.execute_spawn_command(&python_executable, &args);
+        command.execute_spawn_command(python_executable, args)
+    }
+
+    pub fn execute_buffered_command(&self, args: &[&str]) -> Option<CommandResponse> {
+        let python_executable: &WPath = self.environment.get_python_executable();
+        let command: CommandExecute = CommandExecute::new();
+        command.execute_command(python_executable, args)
     }
 }
 
@@ -148,9 +315,9 @@ impl VirtualEnv {
         let mut venv_installed: bool = false;
 
         if let Some(pip_show) = pip_show {
-            let pip_name: Option<&str> = pip_show.get_name();
+            let pip_name: Option<PipPackageName> = pip_show.get_package_name();
             if let Some(pip_name) = pip_name {
-                if pip_name == package_name {
+                if pip_name == PipPackageName::new(package_name) {
                     venv_installed = true;
                 }
             }
@@ -168,7 +335,7 @@ impl VirtualEnv {
         self.print_creating_environment();
         let command: CommandExecute = CommandExecute::new();
         let response: Option<CommandResponse> =
-            command.execute_command(&python_executable, &venv_args);
+            command.execute_command(python_executable, venv_args);
         if let Some(response) = response {
             response.print();
         }
@@ -199,13 +366,114 @@ impl VirtualEnv {
     }
 }
 
+pub struct VirtualEnvExecution {
+    environment_directory: WPath,
+    status: Option<ExitStatus>,
+    response: Option<CommandResponse>,
+    duration: Duration,
+}
+
+impl VirtualEnvExecution {
+    pub fn new(
+        environment_directory: &WPath,
+        status: Option<ExitStatus>,
+        response: Option<CommandResponse>,
+        duration: Duration,
+    ) -> Self {
+        let environment_directory: WPath = environment_directory.clone();
+        let status: Option<ExitStatus> = response
+            .as_ref()
+            .map(|response| *response.get_status())
+            .or(status);
+
+        VirtualEnvExecution {
+            environment_directory,
+            status,
+            response,
+            duration,
+        }
+    }
+
+    pub fn get_environment_directory(&self) -> &WPath {
+        &self.environment_directory
+    }
+
+    pub fn get_duration(&self) -> &Duration {
+        &self.duration
+    }
+
+    pub fn get_exit_code(&self) -> Option<i32> {
+        self.status?.code()
+    }
+
+    pub fn print_block(&self) {
+        let terminal: Terminal = Terminal::new();
+        let string: String = format!("[Environment -> {:?}]", self.environment_directory);
+        terminal.writeln_ansi(&string, &YellowANSI);
+
+        if let Some(response) = &self.response {
+            let stdout: &str = response.get_stdout();
+            let stderr: &str = response.get_stderr();
+
+            if !stdout.is_empty() {
+                terminal.writeln_ansi(stdout, &GreenANSI);
+            }
+            if !stderr.is_empty() {
+                terminal.writeln_ansi(stderr, &RedANSI);
+            }
+        } else {
+            terminal.writeln_ansi("Unable to execute command.", &RedANSI);
+        }
+
+        let separator: String = "-".repeat(10);
+        terminal.writeln_ansi(&separator, &YellowANSI);
+    }
+}
+
 pub struct VirtualEnvSearch {
     deep_search: bool,
+    python_version: Option<String>,
+    path_glob: Option<GlobPattern>,
+    exclude_globs: Vec<GlobPattern>,
+    interactive: bool,
+    assume_yes: bool,
 }
 
 impl VirtualEnvSearch {
     pub fn new(deep_search: bool) -> Self {
-        VirtualEnvSearch { deep_search }
+        VirtualEnvSearch {
+            deep_search,
+            python_version: None,
+            path_glob: None,
+            exclude_globs: Vec::new(),
+            interactive: false,
+            assume_yes: false,
+        }
+    }
+
+    /// Keeps environments whose version starts with the given components, e.g. `3.11`.
+    pub fn set_python_version(&mut self, python_version: &str) {
+        self.python_version = Some(python_version.trim().to_string());
+    }
+
+    pub fn set_path_glob(&mut self, pattern: &str) -> Result<(), regex::Error> {
+        let glob_pattern: GlobPattern = GlobPattern::new(pattern)?;
+        self.path_glob = Some(glob_pattern);
+        Ok(())
+    }
+
+    pub fn add_exclude_glob(&mut self, pattern: &str) -> Result<(), regex::Error> {
+        let glob_pattern: GlobPattern = GlobPattern::new(pattern)?;
+        self.exclude_globs.push(glob_pattern);
+        Ok(())
+    }
+
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.interactive = interactive;
+    }
+
+    pub fn set_assume_yes(&mut self, assume_yes: bool) {
+        self.assume_yes = assume_yes;
     }
 
     pub fn find_configs(&self) -> Vec<VirtualEnvCFG> {
@@ -215,23 +483,20 @@ impl VirtualEnvSearch {
         if let Ok(current_dir) = current_dir {
             let cfg_files: HashSet<FileInfo> = self.find_config(&current_dir);
 
-            if !self.confirm_search(&cfg_files) {
-                return venv_cfgs;
-            }
-
             for file_info in cfg_files {
-                let cfg_file: &PathBuf = file_info.get_path();
-                let cfg_parser: CFGParser = CFGParser::new();
-                let result: Result<Vec<CFGLine>, io::Error> = cfg_parser.from_file(cfg_file);
-
-                if let Ok(result) = result {
-                    let cfg_file: WPath = WPath::from_path_buf(cfg_file);
-                    let venv_cfg: Option<VirtualEnvCFG> = VirtualEnvCFG::new(cfg_file, &result);
-                    if let Some(venv_cfg) = venv_cfg {
+                let cfg_file: WPath = WPath::from_path_buf(file_info.get_path());
+                let venv_cfg: Option<VirtualEnvCFG> = VirtualEnvCFG::from_file(&cfg_file);
+                if let Some(venv_cfg) = venv_cfg {
+                    if self.is_selected(&venv_cfg, &current_dir) {
                         venv_cfgs.push(venv_cfg);
                     }
                 }
             }
+
+            venv_cfgs.sort_by_key(|venv_cfg| {
+                venv_cfg.get_environment_directory().get_canonical_string()
+            });
+            venv_cfgs = self.confirm_search(venv_cfgs);
         }
         venv_cfgs
     }
@@ -258,30 +523,127 @@ impl VirtualEnvSearch {
         files
     }
 
-    fn confirm_search(&self, files: &HashSet<FileInfo>) -> bool {
+    fn is_selected(&self, venv_cfg: &VirtualEnvCFG, root: &PathBuf) -> bool {
+        let environment_directory: WPath = venv_cfg.get_environment_directory();
+        let absolute: String = environment_directory
+            .get_canonical_string()
+            .unwrap_or_default();
+        let relative: String = environment_directory
+            .get_path_buf()
+            .strip_prefix(root)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| absolute.clone());
+
+        let is_match = |glob: &GlobPattern| glob.is_match(&absolute) || glob.is_match(&relative);
+
+        if let Some(path_glob) = &self.path_glob {
+            if !is_match(path_glob) {
+                return false;
+            }
+        }
+
+        if self.exclude_globs.iter().any(is_match) {
+            return false;
+        }
+
+        if let Some(python_version) = &self.python_version {
+            return self.is_version_match(venv_cfg, python_version);
+        }
+        true
+    }
+
+    fn is_version_match(&self, venv_cfg: &VirtualEnvCFG, python_version: &str) -> bool {
+        let version: Option<SemanticVersion> = venv_cfg.get_version();
+        if let Some(version) = version {
+            let (major, minor, patch): (usize, usize, usize) = version.get_3p_version();
+            let components: [usize; 3] = [major, minor, patch];
+
+            return python_version
+                .split('.')
+                .zip(components.iter())
+                .all(|(filter, component)| filter.parse::<usize>().ok() == Some(*component));
+        }
+        false
+    }
+
+    fn confirm_search(&self, venv_cfgs: Vec<VirtualEnvCFG>) -> Vec<VirtualEnvCFG> {
         let terminal: Terminal = Terminal::new();
 
-        if files.len() == 0 {
+        if venv_cfgs.is_empty() {
             let string: &str =
                 "\nNo environments were found.\nTry creating one with: arranger python venv\n";
             terminal.writeln_ansi(string, &RedANSI);
-            return false;
+            return venv_cfgs;
         }
 
         terminal.writeln_ansi("\nFound Environments:", &GreenANSI);
 
-        for file in files {
-            let mut environment_directory: WPath = file.get_path().into();
-            environment_directory.to_directory();
+        for (idx, venv_cfg) in venv_cfgs.iter().enumerate() {
+            let environment_directory: WPath = venv_cfg.get_environment_directory();
 
             let directory_string = environment_directory.get_canonical_string();
             if let Some(directory_string) = directory_string {
                 let path_str: String = format!("[{}]", directory_string);
-                let parts: [&str; 2] = ["Path: ", &path_str];
-                terminal.writeln_parameter(&parts, &CyanANSI);
+                let creator: String = venv_cfg.get_creator().get_string();
+                let parts: [&str; 4] = ["Path: ", &path_str, "Creator: ", &creator];
+
+                if self.interactive {
+                    let number: String = format!("{:>3}) ", idx + 1);
+                    terminal.write_ansi(&number, &YellowANSI);
+                }
+                terminal.writeln_separated_parameters(&parts, &CyanANSI, " | ");
             }
         }
 
-        ConfirmationPrompt::prompt(&terminal)
+        if self.interactive {
+            let selection: Vec<usize> = SelectionPrompt::prompt(&terminal, venv_cfgs.len());
+            return venv_cfgs
+                .into_iter()
+                .enumerate()
+                .filter(|(idx, _)| selection.contains(idx))
+                .map(|(_, venv_cfg)| venv_cfg)
+                .collect();
+        }
+
+        if self.assume_yes {
+            terminal.writeln_ansi("\nContinuing...\n", &GreenANSI);
+            return venv_cfgs;
+        }
+
+        if ConfirmationPrompt::prompt(&terminal) {
+            return venv_cfgs;
+        }
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+
+    use super::VirtualEnvCFG;
+    use crate::misc::path::WPath;
+
+    #[test]
+    fn relocates_only_whole_paths_in_cfg() {
+        let name: String = format!("arranger-venv-cfg-{}.cfg", process::id());
+        let cfg_path: PathBuf = std::env::temp_dir().join(name);
+        let cfg_string: &str = "home = /opt/env/bin\nexecutable = /opt/env2/bin/python3\n\
+                                command = /opt/env/bin/python -m venv /opt/env\n";
+        fs::write(&cfg_path, cfg_string).unwrap();
+
+        let cfg_file: WPath = WPath::from_path_buf(&cfg_path);
+        let mut venv_cfg: VirtualEnvCFG = VirtualEnvCFG::from_file(&cfg_file).unwrap();
+        let relocated: bool = venv_cfg.relocate("/opt/env", "/srv/env");
+        fs::remove_file(&cfg_path).unwrap();
+
+        assert!(relocated);
+        assert_eq!(venv_cfg.get_setting("home"), Some("/srv/env/bin"));
+        let executable: Option<&str> = venv_cfg.get_setting("executable");
+        assert_eq!(executable, Some("/opt/env2/bin/python3"));
+        let command: Option<&str> = venv_cfg.get_setting("command");
+        assert_eq!(command, Some("/srv/env/bin/python -m venv /srv/env"));
     }
 }
@@ -21,9 +21,13 @@ use crate::commands::config::RustSubCommands;
 
 use crate::commands::python::PythonCreateEnvCommand;
 use crate::commands::python::PythonDLCommand;
+use crate::commands::python::PythonDiffEnvsCommand;
 use crate::commands::python::PythonExecuteCommand;
 use crate::commands::python::PythonFixEnvCommand;
+use crate::commands::python::PythonInstallCommand;
+use crate::commands::python::PythonListRemoteCommand;
 use crate::commands::python::PythonPackagesCommand;
+use crate::commands::python::PythonRelocateEnvCommand;
 use crate::commands::rust::RustVSCodeTaskCommand;
 use crate::commands::search::SearchCommand;
 
@@ -57,6 +61,14 @@ async fn main_program() {
                     let mut command: PythonDLCommand = PythonDLCommand::new(option);
                     command.execute_command().await;
                 }
+                PythonSubCommands::PythonInstall(option) => {
+                    let command: PythonInstallCommand = PythonInstallCommand::new(option);
+                    command.execute_command().await;
+                }
+                PythonSubCommands::ListRemote(option) => {
+                    let command: PythonListRemoteCommand = PythonListRemoteCommand::new(option);
+                    command.execute_command().await;
+                }
                 PythonSubCommands::VirtualEnv(option) => {
                     let command: PythonCreateEnvCommand = PythonCreateEnvCommand::new(option);
                     command.execute_command();
@@ -65,10 +77,18 @@ async fn main_program() {
                     let command: PythonFixEnvCommand = PythonFixEnvCommand::new(option);
                     command.execute_command();
                 }
+                PythonSubCommands::RelocateVirtualEnv(option) => {
+                    let command: PythonRelocateEnvCommand = PythonRelocateEnvCommand::new(option);
+                    command.execute_command();
+                }
                 PythonSubCommands::EnvPackages(option) => {
                     let command: PythonPackagesCommand = PythonPackagesCommand::new(option);
                     command.execute_command();
                 }
+                PythonSubCommands::DiffEnvs(option) => {
+                    let command: PythonDiffEnvsCommand = PythonDiffEnvsCommand::new(option);
+                    command.execute_command();
+                }
                 PythonSubCommands::VirtualEnvExecute(option) => {
                     let command: PythonExecuteCommand = PythonExecuteCommand::new(option);
                     command.execute_command();
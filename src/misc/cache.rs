@@ -0,0 +1,61 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use dirs;
+
+/// Downloads shared between invocations, stored under the user cache directory and
+/// keyed by the host and path of their URL.
+pub struct DownloadCache {
+    directory: PathBuf,
+}
+
+impl DownloadCache {
+    pub fn new() -> Option<Self> {
+        let cache_dir: PathBuf = dirs::cache_dir()?;
+        let directory: PathBuf = cache_dir.join("arranger").join("downloads");
+        Some(DownloadCache { directory })
+    }
+
+    pub fn get_directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Maps `https://host/a/b/file` to `<cache>/host/a/b/file`.
+    pub fn get_path(&self, url: &str) -> Option<PathBuf> {
+        let (_, location): (&str, &str) = url.split_once("://")?;
+        let location: &str = location.split(['?', '#']).next()?;
+
+        let mut path: PathBuf = self.directory.clone();
+        for segment in location.split('/').filter(|segment| !segment.is_empty()) {
+            // Keep the entry inside the cache directory.
+            if segment == "." || segment == ".." {
+                return None;
+            }
+            path.push(segment.replace(':', "_"));
+        }
+
+        if path == self.directory || url.ends_with('/') {
+            return None;
+        }
+        Some(path)
+    }
+
+    /// Copies a cached file to `destination` through a temporary file, so an
+    /// interrupted copy never leaves a truncated destination behind.
+    pub fn copy_to(&self, cached_path: &Path, destination: &Path) -> Result<(), io::Error> {
+        if let Some(parent) = destination.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let mut temporary_path: OsString = destination.as_os_str().to_os_string();
+        temporary_path.push(".tmp");
+        let temporary_path: PathBuf = PathBuf::from(temporary_path);
+
+        fs::copy(cached_path, &temporary_path)?;
+        fs::rename(&temporary_path, destination)
+    }
+}
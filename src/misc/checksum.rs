@@ -0,0 +1,53 @@
+use std::fs::File;
+use std::io;
+use std::io::Read;
+
+use sha2::{Digest, Sha256};
+
+use super::path::WPath;
+
+pub struct Sha256Checksum;
+
+impl Sha256Checksum {
+    pub fn new() -> Self {
+        Sha256Checksum
+    }
+
+    /// Returns the lowercase hex SHA-256 digest of a file, reading it in chunks.
+    pub fn get_file_digest(&self, file_path: &WPath) -> Result<String, io::Error> {
+        let mut file: File = File::open(file_path)?;
+        let mut hasher: Sha256 = Sha256::new();
+        let mut buffer: Vec<u8> = vec![0; 64 * 1024];
+
+        loop {
+            let length: usize = file.read(&mut buffer)?;
+            if length == 0 {
+                break;
+            }
+            hasher.update(&buffer[..length]);
+        }
+
+        let digest: Vec<u8> = hasher.finalize().to_vec();
+        Ok(self.to_hex(&digest))
+    }
+
+    pub fn get_bytes_digest(&self, bytes: &[u8]) -> String {
+        let digest: Vec<u8> = Sha256::digest(bytes).to_vec();
+        self.to_hex(&digest)
+    }
+
+    pub fn to_hex(&self, bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Checks that a string is a 64 character hex SHA-256 digest.
+    pub fn is_digest(&self, string: &str) -> bool {
+        string.len() == 64 && string.chars().all(|char| char.is_ascii_hexdigit())
+    }
+}
+
+impl Default for Sha256Checksum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
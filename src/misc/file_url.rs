@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+/// Converts between local paths and `file://` URLs, for mirrors and direct URLs that
+/// point at the local machine.
+pub struct FileUrl;
+
+impl FileUrl {
+    /// Returns the local path of a `file://` URL, or `None` for other schemes. The host
+    /// may be empty or `localhost`, and percent-escapes such as `%20` are decoded.
+    pub fn get_local_path(url: &str) -> Option<PathBuf> {
+        let location: &str = url.strip_prefix("file://")?;
+        let (host, path): (&str, &str) = match location.find('/') {
+            Some(idx) => location.split_at(idx),
+            None => (location, ""),
+        };
+        let path: String = Self::percent_decode(path);
+
+        if !host.is_empty() && !host.eq_ignore_ascii_case("localhost") {
+            // Any other host names a network share, e.g. `file://server/share/...`.
+            return Some(PathBuf::from(format!("//{}{}", host, path)));
+        }
+        // Windows paths arrive as `file:///C:/...`.
+        let is_drive: bool = path.len() > 2 && path.as_bytes()[2] == b':';
+        let path: &str = match path.strip_prefix('/') {
+            Some(drive_path) if is_drive => drive_path,
+            _ => &path,
+        };
+        Some(PathBuf::from(path))
+    }
+
+    /// Builds a `file://` URL for a local path using `/` separators, such as
+    /// `/srv/python` or `C:/Python`, percent-encoding characters unsafe in a URL.
+    pub fn get_file_url(path: &str) -> String {
+        let mut url: String = String::from("file://");
+        if !path.starts_with('/') {
+            url.push('/');
+        }
+        for byte in path.bytes() {
+            let is_safe: bool = byte.is_ascii_alphanumeric() || b"/:-._~".contains(&byte);
+            if is_safe {
+                url.push(byte as char);
+            } else {
+                url.push_str(&format!("%{:02X}", byte));
+            }
+        }
+        url
+    }
+}
+
+impl FileUrl {
+    /// Decodes `%XX` escapes, keeping malformed escapes as they are.
+    fn percent_decode(string: &str) -> String {
+        let bytes: &[u8] = string.as_bytes();
+        let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+        let mut idx: usize = 0;
+
+        while idx < bytes.len() {
+            let escape: Option<u8> = match bytes.get(idx + 1..idx + 3) {
+                Some(hex) if bytes[idx] == b'%' && hex.iter().all(u8::is_ascii_hexdigit) => {
+                    let hex: &str = &string[idx + 1..idx + 3];
+                    u8::from_str_radix(hex, 16).ok()
+                }
+                _ => None,
+            };
+            match escape {
+                Some(byte) => {
+                    decoded.push(byte);
+                    idx += 3;
+                }
+                None => {
+                    decoded.push(bytes[idx]);
+                    idx += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_local_file_urls() {
+        let path: Option<PathBuf> = FileUrl::get_local_path("file:///srv/python%20mirror/3.12.0/");
+        assert_eq!(path, Some(PathBuf::from("/srv/python mirror/3.12.0/")));
+
+        let path: Option<PathBuf> = FileUrl::get_local_path("file://localhost/srv/python/");
+        assert_eq!(path, Some(PathBuf::from("/srv/python/")));
+
+        let path: Option<PathBuf> = FileUrl::get_local_path("file:///C:/Python%2Fmirror/");
+        assert_eq!(path, Some(PathBuf::from("C:/Python/mirror/")));
+    }
+
+    #[test]
+    fn keeps_malformed_escapes_and_network_hosts() {
+        let path: Option<PathBuf> = FileUrl::get_local_path("file:///srv/100%/python%2");
+        assert_eq!(path, Some(PathBuf::from("/srv/100%/python%2")));
+
+        let path: Option<PathBuf> = FileUrl::get_local_path("file://server/share/python/");
+        assert_eq!(path, Some(PathBuf::from("//server/share/python/")));
+
+        assert_eq!(FileUrl::get_local_path("https://www.python.org/"), None);
+    }
+
+    #[test]
+    fn builds_file_urls_that_round_trip() {
+        let url: String = FileUrl::get_file_url("/srv/python mirror/100%/");
+        assert_eq!(url, "file:///srv/python%20mirror/100%25/");
+        let path: Option<PathBuf> = FileUrl::get_local_path(&url);
+        assert_eq!(path, Some(PathBuf::from("/srv/python mirror/100%/")));
+
+        assert_eq!(FileUrl::get_file_url("C:/Python"), "file:///C:/Python");
+    }
+}
@@ -1,69 +1,582 @@
 use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
 use std::fs::File;
+use std::fs::OpenOptions;
 use std::io;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 use hyper::body;
+use hyper::body::HttpBody;
 use hyper::client::Client;
 use hyper::client::HttpConnector;
+use hyper::header;
 use hyper::http;
 use hyper::http::uri;
 use hyper::Body;
 use hyper::Request;
 use hyper::Response;
+use hyper::StatusCode;
 use hyper::Uri;
+use hyper_proxy::ProxyConnector;
 use hyper_tls::HttpsConnector;
+use tokio::time;
+use tokio::time::error::Elapsed;
+
+use super::file_url::FileUrl;
+use super::progress::DownloadProgress;
+use super::proxy::ProxySettings;
+
+type Connector = ProxyConnector<HttpsConnector<HttpConnector>>;
+
+#[derive(Debug)]
+pub enum HTTPSError {
+    Status {
+        url: String,
+        status: StatusCode,
+    },
+    ContentType {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl HTTPSError {
+    pub fn get_url(&self) -> &str {
+        match self {
+            HTTPSError::Status { url, .. } => url,
+            HTTPSError::ContentType { url, .. } => url,
+        }
+    }
+}
+
+impl fmt::Display for HTTPSError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HTTPSError::Status { url, status } => {
+                write!(f, "server responded with {} for {}", status, url)
+            }
+            HTTPSError::ContentType {
+                url,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected {} but received {} from {}",
+                expected, actual, url
+            ),
+        }
+    }
+}
+
+impl Error for HTTPSError {}
+
+#[derive(Debug, Clone)]
+pub struct HTTPSConfig {
+    max_redirects: usize,
+    connect_timeout: Duration,
+    read_timeout: Duration,
+    max_retries: usize,
+    retry_delay: Duration,
+    proxy: ProxySettings,
+}
+
+impl HTTPSConfig {
+    /// Defaults to 10 redirects, 30s timeouts and 3 retries starting 1s apart, with
+    /// proxies taken from the environment.
+    pub fn new() -> Self {
+        HTTPSConfig {
+            max_redirects: 10,
+            connect_timeout: Duration::from_secs(30),
+            read_timeout: Duration::from_secs(30),
+            max_retries: 3,
+            retry_delay: Duration::from_secs(1),
+            proxy: ProxySettings::from_environment(),
+        }
+    }
+
+    pub fn set_max_redirects(&mut self, max_redirects: usize) {
+        self.max_redirects = max_redirects;
+    }
+
+    pub fn set_connect_timeout(&mut self, connect_timeout: Duration) {
+        self.connect_timeout = connect_timeout;
+    }
+
+    /// Limits the wait for response headers and for each chunk of the body.
+    pub fn set_read_timeout(&mut self, read_timeout: Duration) {
+        self.read_timeout = read_timeout;
+    }
+
+    pub fn set_max_retries(&mut self, max_retries: usize) {
+        self.max_retries = max_retries;
+    }
+
+    /// Sets the delay before the first retry, doubling for each one after it.
+    pub fn set_retry_delay(&mut self, retry_delay: Duration) {
+        self.retry_delay = retry_delay;
+    }
+
+    pub fn set_proxy(&mut self, proxy: ProxySettings) {
+        self.proxy = proxy;
+    }
+}
+
+impl Default for HTTPSConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub struct HTTPS {
-    client: Client<HttpsConnector<HttpConnector>>,
+    client: Client<Connector>,
+    connector: Connector,
+    config: HTTPSConfig,
 }
 
 impl HTTPS {
     pub fn new() -> Self {
-        let https: HttpsConnector<HttpConnector> = HttpsConnector::new();
-        let client: Client<HttpsConnector<HttpConnector>> = Self::get_client(https);
-        HTTPS { client }
+        Self::from_config(HTTPSConfig::new())
+    }
+
+    pub fn from_config(config: HTTPSConfig) -> Self {
+        let connector: Connector = Self::get_connector(&config);
+        let client: Client<Connector> = Self::get_client(connector.clone());
+        HTTPS {
+            client,
+            connector,
+            config,
+        }
     }
 
+    /// Downloads into the current directory under the file name from the URL.
     pub async fn download_file(&self, url: &str) -> Result<String, Box<dyn Error>> {
         let uri: Uri = Uri::from_str(url).map_err(|e| self.map_uri_error(e))?;
         let file_name: String = self.get_file_name(&uri)?;
-        let response: Response<Body> = self.download(&uri).await?;
-        self.write_file(file_name, response).await
+        self.download_to(url, Path::new(&file_name)).await?;
+        Ok(file_name)
+    }
+
+    /// Streams the file into `<path>.part`, resuming a previous partial download with
+    /// a Range request, and renames it to `<path>` once the body is complete.
+    ///
+    /// The ETag or Last-Modified of the response is kept in `<path>.part.validator` and
+    /// sent as `If-Range`, so the server restarts the body if the file has changed since.
+    /// A connection lost mid-body is resumed the same way, up to `max_retries` times.
+    pub async fn download_to(&self, url: &str, file_path: &Path) -> Result<(), Box<dyn Error>> {
+        let part_path: PathBuf = self.get_part_path(file_path);
+        let validator_path: PathBuf = self.get_validator_path(file_path);
+        if let Some(parent) = file_path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        if let Some(source_path) = FileUrl::get_local_path(url) {
+            fs::copy(source_path, &part_path)?;
+            fs::rename(&part_path, file_path)?;
+            return Ok(());
+        }
+        let uri: Uri = Uri::from_str(url).map_err(|e| self.map_uri_error(e))?;
+
+        // Without a validator a partial file cannot be checked, so it is downloaded again.
+        let mut validator: Option<String> = fs::read_to_string(&validator_path).ok();
+        let mut attempt: usize = 0;
+
+        loop {
+            let resumed: u64 = self.get_resume_offset(&part_path, &validator);
+            let mut response: Response<Body> =
+                self.download(&uri, resumed, validator.as_deref()).await?;
+
+            if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+                // The server reports the full length, so a complete part file only needs renaming.
+                if resumed > 0 && self.get_content_range_total(&response) == Some(resumed) {
+                    fs::rename(&part_path, file_path)?;
+                    let _ = fs::remove_file(&validator_path);
+                    return Ok(());
+                }
+                response = self.download(&uri, 0, None).await?;
+            }
+
+            // An error page is not worth resuming, so drop any partial file left from earlier.
+            if let Err(error) = self.validate_download(&uri, &response) {
+                let _ = fs::remove_file(&part_path);
+                let _ = fs::remove_file(&validator_path);
+                return Err(Box::new(error));
+            }
+
+            if response.status() != StatusCode::PARTIAL_CONTENT {
+                validator = self.get_validator(&response);
+                match &validator {
+                    Some(validator) => fs::write(&validator_path, validator)?,
+                    None => {
+                        let _ = fs::remove_file(&validator_path);
+                    }
+                }
+            }
+
+            let result: Result<(), Box<dyn Error>> =
+                self.write_file(file_path, response, resumed).await;
+            match result {
+                Ok(()) => {
+                    let _ = fs::remove_file(&validator_path);
+                    return Ok(());
+                }
+                Err(error)
+                    if self.is_interrupted_body(error.as_ref())
+                        && attempt < self.config.max_retries =>
+                {
+                    let delay: Duration = self.config.retry_delay * 2u32.pow(attempt as u32);
+                    time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
     }
 
     pub async fn get_response_body(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        self.get_typed_response_body(url, None).await
+    }
+
+    /// Like `get_response_body`, but fails unless the response has the given media type,
+    /// e.g. `text/html` for directory listings.
+    pub async fn get_typed_response_body(
+        &self,
+        url: &str,
+        content_type: Option<&str>,
+    ) -> Result<String, Box<dyn Error>> {
+        if let Some(path) = FileUrl::get_local_path(url) {
+            return Ok(fs::read_to_string(path)?);
+        }
+
         let uri: Uri = Uri::from_str(url).map_err(|e| self.map_uri_error(e))?;
-        let resp: Response<Body> = self.client.get(uri).await?;
-        let bytes: body::Bytes = body::to_bytes(resp.into_body()).await?;
-        let resp_string: String = String::from_utf8(bytes.to_vec())?;
+        let resp: Response<Body> = self.get_response(&uri, 0, None).await?;
+        self.validate_status(&uri, &resp)?;
+        if let Some(content_type) = content_type {
+            self.validate_content_type(&uri, &resp, content_type)?;
+        }
+        let mut body: Body = resp.into_body();
+
+        let mut bytes: Vec<u8> = Vec::new();
+        while let Some(chunk) = self.read_chunk(&mut body).await? {
+            bytes.extend_from_slice(&chunk);
+        }
+        let resp_string: String = String::from_utf8(bytes)?;
 
         Ok(resp_string)
     }
 }
 
 impl HTTPS {
-    async fn download(&self, uri: &Uri) -> Result<hyper::Response<Body>, Box<dyn Error>> {
-        let req: Request<Body> = self.get_request_body(uri).map_err(Box::new)?;
-        let resp: Response<Body> = self.client.request(req).await.map_err(Box::new)?;
+    async fn download(
+        &self,
+        uri: &Uri,
+        resumed: u64,
+        validator: Option<&str>,
+    ) -> Result<hyper::Response<Body>, Box<dyn Error>> {
+        self.get_response(uri, resumed, validator).await
+    }
+
+    /// Follows up to `max_redirects` redirects, retrying each request as configured.
+    async fn get_response(
+        &self,
+        uri: &Uri,
+        resumed: u64,
+        validator: Option<&str>,
+    ) -> Result<Response<Body>, Box<dyn Error>> {
+        let mut uri: Uri = uri.clone();
+        let mut redirects: usize = 0;
+
+        loop {
+            let response: Response<Body> = self.send_with_retries(&uri, resumed, validator).await?;
+            let location: Option<Uri> = self.get_redirect_location(&uri, &response);
+            let location: Uri = match location {
+                Some(location) => location,
+                None => return Ok(response),
+            };
+
+            if redirects >= self.config.max_redirects {
+                let message: String = format!(
+                    "Too many redirects, stopped after {}",
+                    self.config.max_redirects
+                );
+                return Err(Box::new(io::Error::other(message)));
+            }
+            redirects += 1;
+            uri = location;
+        }
+    }
+
+    /// Retries connection errors, timeouts and 5xx responses with exponential backoff.
+    async fn send_with_retries(
+        &self,
+        uri: &Uri,
+        resumed: u64,
+        validator: Option<&str>,
+    ) -> Result<Response<Body>, Box<dyn Error>> {
+        let mut attempt: usize = 0;
+
+        loop {
+            let result: Result<Response<Body>, Box<dyn Error>> =
+                self.send(uri, resumed, validator).await;
+            let is_retryable: bool = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(error) => self.is_retryable_error(error.as_ref()),
+            };
+            if !is_retryable || attempt >= self.config.max_retries {
+                return result;
+            }
+
+            let delay: Duration = self.config.retry_delay * 2u32.pow(attempt as u32);
+            time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send(
+        &self,
+        uri: &Uri,
+        resumed: u64,
+        validator: Option<&str>,
+    ) -> Result<Response<Body>, Box<dyn Error>> {
+        let mut req: Request<Body> = self
+            .get_request_body(uri, resumed, validator)
+            .map_err(Box::new)?;
+        // Plain HTTP requests through a proxy carry its headers themselves.
+        if let Some(headers) = self.connector.http_headers(uri) {
+            req.headers_mut().extend(headers.clone());
+        }
+
+        let resp: Result<Result<Response<Body>, hyper::Error>, Elapsed> =
+            time::timeout(self.config.read_timeout, self.client.request(req)).await;
+        let resp: Response<Body> = resp.map_err(|_| self.get_timeout_error())??;
         Ok(resp)
     }
 
+    async fn read_chunk(&self, body: &mut Body) -> Result<Option<body::Bytes>, Box<dyn Error>> {
+        let chunk: Result<Option<Result<body::Bytes, hyper::Error>>, Elapsed> =
+            time::timeout(self.config.read_timeout, body.data()).await;
+        let chunk: Option<Result<body::Bytes, hyper::Error>> =
+            chunk.map_err(|_| self.get_timeout_error())?;
+        match chunk {
+            Some(chunk) => Ok(Some(chunk?)),
+            None => Ok(None),
+        }
+    }
+
+    fn is_retryable_error(&self, error: &(dyn Error + 'static)) -> bool {
+        if let Some(error) = error.downcast_ref::<hyper::Error>() {
+            return error.is_connect() || error.is_incomplete_message() || error.is_closed();
+        }
+        if let Some(error) = error.downcast_ref::<io::Error>() {
+            return error.kind() == io::ErrorKind::TimedOut;
+        }
+        false
+    }
+
+    /// A body cut short by the connection, as opposed to a failure writing the file.
+    fn is_interrupted_body(&self, error: &(dyn Error + 'static)) -> bool {
+        error.downcast_ref::<hyper::Error>().is_some() || self.is_retryable_error(error)
+    }
+
+    fn get_redirect_location(&self, uri: &Uri, response: &Response<Body>) -> Option<Uri> {
+        if !response.status().is_redirection() || response.status() == StatusCode::NOT_MODIFIED {
+            return None;
+        }
+        let location: &str = response.headers().get(header::LOCATION)?.to_str().ok()?;
+        self.resolve_location(uri, location)
+    }
+
+    /// Resolves absolute, host-relative and path-relative `Location` values against `uri`.
+    fn resolve_location(&self, uri: &Uri, location: &str) -> Option<Uri> {
+        if location.contains("://") {
+            return Uri::from_str(location).ok();
+        }
+
+        let scheme: &str = uri.scheme_str()?;
+        let authority: &str = uri.authority()?.as_str();
+        let location: String = if let Some(location) = location.strip_prefix("//") {
+            format!("{}://{}", scheme, location)
+        } else if location.starts_with('/') {
+            format!("{}://{}{}", scheme, authority, location)
+        } else {
+            let directory: &str = uri.path().rsplit_once('/').map_or("", |(dir, _)| dir);
+            format!("{}://{}{}/{}", scheme, authority, directory, location)
+        };
+        Uri::from_str(&location).ok()
+    }
+
+    fn validate_status(&self, uri: &Uri, response: &Response<Body>) -> Result<(), HTTPSError> {
+        if response.status().is_success() {
+            return Ok(());
+        }
+        Err(HTTPSError::Status {
+            url: uri.to_string(),
+            status: response.status(),
+        })
+    }
+
+    fn validate_content_type(
+        &self,
+        uri: &Uri,
+        response: &Response<Body>,
+        expected: &str,
+    ) -> Result<(), HTTPSError> {
+        let actual: Option<String> = self.get_content_type(response);
+        match actual {
+            Some(actual) if actual != expected => Err(HTTPSError::ContentType {
+                url: uri.to_string(),
+                expected: expected.to_string(),
+                actual,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Installers and archives are never served as HTML, so an HTML body is an error page.
+    fn validate_download(&self, uri: &Uri, response: &Response<Body>) -> Result<(), HTTPSError> {
+        self.validate_status(uri, response)?;
+        if self.get_content_type(response).as_deref() == Some("text/html") {
+            return Err(HTTPSError::ContentType {
+                url: uri.to_string(),
+                expected: "a file download".to_string(),
+                actual: "text/html".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the media type without parameters, e.g. `text/html` for `text/html; charset=utf-8`.
+    fn get_content_type(&self, response: &Response<Body>) -> Option<String> {
+        let content_type: &str = response
+            .headers()
+            .get(header::CONTENT_TYPE)?
+            .to_str()
+            .ok()?;
+        let media_type: &str = content_type.split(';').next()?.trim();
+        Some(media_type.to_lowercase())
+    }
+
     async fn write_file(
         &self,
-        file_name: String,
+        file_path: &Path,
         response: hyper::Response<Body>,
-    ) -> Result<String, Box<dyn Error>> {
-        let mut file: File = File::create(&file_name)?;
-        let bytes: body::Bytes = body::to_bytes(response.into_body()).await?;
-        file.write_all(&bytes)?;
-        Ok(file_name)
+        resumed: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let part_path: PathBuf = self.get_part_path(file_path);
+
+        // Servers that ignore the Range header send the whole body again.
+        let is_partial: bool = response.status() == StatusCode::PARTIAL_CONTENT;
+        let resumed: u64 = if is_partial { resumed } else { 0 };
+        let total: Option<u64> = if is_partial {
+            self.get_content_range_total(&response)
+        } else {
+            self.get_content_length(&response)
+        };
+
+        let mut file: File = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(is_partial)
+            .truncate(!is_partial)
+            .open(&part_path)?;
+
+        let file_name: String = file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut progress: DownloadProgress = DownloadProgress::new(&file_name, resumed, total);
+        let result: Result<(), Box<dyn Error>> = self
+            .write_body(&mut file, response.into_body(), &mut progress)
+            .await;
+        progress.finalize();
+        result?;
+
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&part_path, file_path)?;
+        Ok(())
+    }
+
+    async fn write_body(
+        &self,
+        file: &mut File,
+        mut body: Body,
+        progress: &mut DownloadProgress,
+    ) -> Result<(), Box<dyn Error>> {
+        while let Some(chunk) = self.read_chunk(&mut body).await? {
+            file.write_all(&chunk)?;
+            progress.add_bytes(chunk.len());
+        }
+        Ok(())
+    }
+
+    fn get_content_length(&self, response: &Response<Body>) -> Option<u64> {
+        let content_length: &str = response
+            .headers()
+            .get(header::CONTENT_LENGTH)?
+            .to_str()
+            .ok()?;
+        content_length.parse::<u64>().ok()
+    }
+
+    /// Reads the full length from a `Content-Range: bytes <start>-<end>/<total>` header.
+    fn get_content_range_total(&self, response: &Response<Body>) -> Option<u64> {
+        let content_range: &str = response
+            .headers()
+            .get(header::CONTENT_RANGE)?
+            .to_str()
+            .ok()?;
+        let (_, total): (&str, &str) = content_range.rsplit_once('/')?;
+        total.parse::<u64>().ok()
+    }
+
+    fn get_part_path(&self, file_path: &Path) -> PathBuf {
+        let mut part_path: OsString = file_path.as_os_str().to_os_string();
+        part_path.push(".part");
+        PathBuf::from(part_path)
+    }
+
+    fn get_validator_path(&self, file_path: &Path) -> PathBuf {
+        let mut validator_path: OsString = file_path.as_os_str().to_os_string();
+        validator_path.push(".part.validator");
+        PathBuf::from(validator_path)
+    }
+
+    /// Resumes from the end of the part file when its validator is known, otherwise
+    /// starts over.
+    fn get_resume_offset(&self, part_path: &Path, validator: &Option<String>) -> u64 {
+        if validator.is_none() {
+            return 0;
+        }
+        fs::metadata(part_path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0)
+    }
+
+    /// Returns a strong ETag, or else the Last-Modified date, for use in `If-Range`.
+    fn get_validator(&self, response: &Response<Body>) -> Option<String> {
+        let headers: &header::HeaderMap = response.headers();
+        let etag: Option<&str> = headers
+            .get(header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .filter(|etag| !etag.starts_with("W/"));
+        let last_modified: Option<&str> = headers
+            .get(header::LAST_MODIFIED)
+            .and_then(|last_modified| last_modified.to_str().ok());
+        etag.or(last_modified)
+            .map(|validator| validator.to_string())
     }
 
     fn get_file_name(&self, uri: &Uri) -> Result<String, io::Error> {
         let path_segments: Vec<&str> = uri.path().split('/').collect::<Vec<&str>>();
-        let last_segment: Option<&str> = path_segments.last().and_then(|s| Some(*s));
+        let last_segment: Option<&str> = path_segments.last().copied();
 
         if let Some(name) = last_segment {
             if !name.is_empty() {
@@ -74,12 +587,39 @@ impl HTTPS {
         Err(self.get_file_name_error())
     }
 
-    fn get_client(https: HttpsConnector<HttpConnector>) -> Client<HttpsConnector<HttpConnector>> {
-        Client::builder().build::<_, Body>(https)
+    fn get_connector(config: &HTTPSConfig) -> Connector {
+        let mut http: HttpConnector = HttpConnector::new();
+        http.enforce_http(false);
+        http.set_connect_timeout(Some(config.connect_timeout));
+
+        let https: HttpsConnector<HttpConnector> = HttpsConnector::new_with_connector(http);
+        let proxy: Result<Connector, io::Error> = ProxyConnector::new(https.clone());
+        let mut connector: Connector = match proxy {
+            Ok(connector) => connector,
+            Err(_) => ProxyConnector::unsecured(https),
+        };
+        connector.extend_proxies(config.proxy.get_proxies());
+        connector
     }
 
-    fn get_request_body(&self, uri: &Uri) -> Result<Request<Body>, http::Error> {
-        Request::get(uri.clone()).body(Body::empty())
+    fn get_client(connector: Connector) -> Client<Connector> {
+        Client::builder().build::<_, Body>(connector)
+    }
+
+    fn get_request_body(
+        &self,
+        uri: &Uri,
+        resumed: u64,
+        validator: Option<&str>,
+    ) -> Result<Request<Body>, http::Error> {
+        let mut request: http::request::Builder = Request::get(uri.clone());
+        if resumed > 0 {
+            request = request.header(header::RANGE, format!("bytes={}-", resumed));
+            if let Some(validator) = validator {
+                request = request.header(header::IF_RANGE, validator);
+            }
+        }
+        request.body(Body::empty())
     }
 
     fn get_file_name_error(&self) -> io::Error {
@@ -90,7 +630,114 @@ impl HTTPS {
         error
     }
 
+    fn get_timeout_error(&self) -> io::Error {
+        io::Error::new(io::ErrorKind::TimedOut, "Timed out waiting for the server")
+    }
+
     fn map_uri_error(&self, error: uri::InvalidUri) -> io::Error {
         io::Error::new(io::ErrorKind::InvalidInput, error)
     }
 }
+
+impl Default for HTTPS {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::Receiver;
+    use std::vec::IntoIter;
+
+    use super::*;
+    use crate::misc::test_utils;
+
+    /// Answers each connection with the next raw response. Returns the URL of the
+    /// archive and the lowercased request heads received.
+    fn serve(responses: Vec<Vec<u8>>) -> (String, Receiver<String>) {
+        let mut responses: IntoIter<Vec<u8>> = responses.into_iter();
+        let (base_url, requests): (String, Receiver<String>) =
+            test_utils::serve(move |_| responses.next().unwrap_or_default());
+        (format!("{}python.tgz", base_url), requests)
+    }
+
+    fn get_response(status: &str, headers: &[&str], body: &str, length: usize) -> Vec<u8> {
+        test_utils::get_response(status, headers, body.as_bytes(), length)
+    }
+
+    fn get_client() -> HTTPS {
+        HTTPS::from_config(test_utils::get_https_config())
+    }
+
+    fn get_temp_path(name: &str) -> PathBuf {
+        test_utils::get_temp_path("https", name)
+    }
+
+    #[tokio::test]
+    async fn resumes_dropped_body_with_if_range() {
+        let (url, requests): (String, Receiver<String>) = serve(vec![
+            get_response("200 OK", &["ETag: \"v1\""], "0123", 10),
+            get_response(
+                "206 Partial Content",
+                &["ETag: \"v1\"", "Content-Range: bytes 4-9/10"],
+                "456789",
+                6,
+            ),
+        ]);
+        let file_path: PathBuf = get_temp_path("resume");
+
+        get_client().download_to(&url, &file_path).await.unwrap();
+        let content: String = fs::read_to_string(&file_path).unwrap();
+        fs::remove_file(&file_path).unwrap();
+        assert_eq!(content, "0123456789");
+
+        let retry: String = requests.iter().nth(1).unwrap().to_lowercase();
+        assert!(retry.contains("range: bytes=4-"));
+        assert!(retry.contains("if-range: \"v1\""));
+        assert!(!get_client().get_validator_path(&file_path).exists());
+    }
+
+    #[tokio::test]
+    async fn restarts_dropped_body_when_file_changed() {
+        // The server ignores the range once its ETag no longer matches If-Range.
+        let (url, _requests): (String, Receiver<String>) = serve(vec![
+            get_response("200 OK", &["ETag: \"v1\""], "0123", 10),
+            get_response("200 OK", &["ETag: \"v2\""], "ABCDEFGHIJ", 10),
+        ]);
+        let file_path: PathBuf = get_temp_path("changed");
+
+        get_client().download_to(&url, &file_path).await.unwrap();
+        let content: String = fs::read_to_string(&file_path).unwrap();
+        fs::remove_file(&file_path).unwrap();
+        assert_eq!(content, "ABCDEFGHIJ");
+    }
+
+    #[tokio::test]
+    async fn resumes_part_file_with_stored_validator() {
+        let (url, requests): (String, Receiver<String>) = serve(vec![get_response(
+            "206 Partial Content",
+            &[
+                "Last-Modified: Wed, 02 Oct 2024 12:00:00 GMT",
+                "Content-Range: bytes 4-9/10",
+            ],
+            "456789",
+            6,
+        )]);
+        let https: HTTPS = get_client();
+        let file_path: PathBuf = get_temp_path("stored");
+        let validator: &str = "Wed, 02 Oct 2024 12:00:00 GMT";
+        fs::write(https.get_part_path(&file_path), "0123").unwrap();
+        fs::write(https.get_validator_path(&file_path), validator).unwrap();
+
+        https.download_to(&url, &file_path).await.unwrap();
+        let content: String = fs::read_to_string(&file_path).unwrap();
+        fs::remove_file(&file_path).unwrap();
+        assert_eq!(content, "0123456789");
+
+        let request: String = requests.recv().unwrap().to_lowercase();
+        let if_range: String = format!("if-range: {}", validator.to_lowercase());
+        assert!(request.contains("range: bytes=4-"));
+        assert!(request.contains(&if_range));
+    }
+}
@@ -1,8 +1,16 @@
 pub mod ansi_support;
+pub mod cache;
+pub mod checksum;
+pub mod file_url;
 pub mod https;
 pub mod interrupt_handler;
 pub mod linked_hashmap;
 pub mod path;
+pub mod progress;
+pub mod proxy;
+pub mod settings;
 pub mod shell;
 pub mod table_display;
+#[cfg(test)]
+pub mod test_utils;
 pub mod version;
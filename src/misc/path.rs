@@ -120,6 +120,41 @@ impl WPath {
         }
         None
     }
+
+    /// Replaces `from` where it is a whole path or a path prefix, so `/a/env` does not
+    /// match inside `/a/env2`. Returns `None` when nothing was replaced.
+    pub fn replace_path_bytes(content: &[u8], from: &[u8], to: &[u8]) -> Option<Vec<u8>> {
+        let mut replaced: Vec<u8> = Vec::with_capacity(content.len());
+        let mut found: bool = false;
+        let mut idx: usize = 0;
+
+        while idx < content.len() {
+            let is_match: bool = content[idx..].starts_with(from)
+                && Self::is_path_boundary(content.get(idx + from.len()));
+            if is_match {
+                replaced.extend_from_slice(to);
+                idx += from.len();
+                found = true;
+            } else {
+                replaced.push(content[idx]);
+                idx += 1;
+            }
+        }
+
+        if found {
+            return Some(replaced);
+        }
+        None
+    }
+}
+
+impl WPath {
+    fn is_path_boundary(byte: Option<&u8>) -> bool {
+        match byte {
+            Some(byte) => matches!(byte, b'/' | b'\\' | b'"' | b'\'') || byte.is_ascii_whitespace(),
+            None => true,
+        }
+    }
 }
 
 impl Debug for WPath {
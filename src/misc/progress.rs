@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+use crate::search::formatters::format_size;
+use crate::terminal::ConsoleWriter;
+
+/// Live progress line for a download, redrawn in place through `ConsoleWriter`.
+pub struct DownloadProgress {
+    writer: ConsoleWriter,
+    file_name: String,
+    downloaded: u64,
+    resumed: u64,
+    total: Option<u64>,
+    time: Instant,
+    display_time: Instant,
+    display_interval: Duration,
+}
+
+impl DownloadProgress {
+    /// `resumed` is the number of bytes already on disk from a previous attempt.
+    pub fn new(file_name: &str, resumed: u64, total: Option<u64>) -> Self {
+        let writer: ConsoleWriter = ConsoleWriter::new();
+        writer.setup_console_configuration();
+        let file_name: String = file_name.to_string();
+        let time: Instant = Instant::now();
+        let display_time: Instant = Instant::now();
+        let display_interval: Duration = Duration::from_millis(100);
+
+        DownloadProgress {
+            writer,
+            file_name,
+            downloaded: resumed,
+            resumed,
+            total,
+            time,
+            display_time,
+            display_interval,
+        }
+    }
+
+    pub fn add_bytes(&mut self, bytes: usize) {
+        self.downloaded += bytes as u64;
+        if self.display_time.elapsed() >= self.display_interval {
+            self.write_progress();
+            self.display_time = Instant::now();
+        }
+    }
+
+    pub fn finalize(&mut self) {
+        self.write_progress();
+        self.writer.go_to_end();
+        self.writer.reset_console_configuration();
+        println!();
+    }
+}
+
+impl DownloadProgress {
+    fn write_progress(&mut self) {
+        let downloaded: String = format_size(self.downloaded as usize);
+        let speed: String = format_size(self.get_speed() as usize);
+
+        let progress: String = match self.total {
+            Some(total) if total > 0 => {
+                let fraction: f64 = (self.downloaded as f64 / total as f64).min(1.0);
+                format!(
+                    "{} {} {:>5.1}% {} / {} {}/s",
+                    self.file_name,
+                    self.get_bar(fraction),
+                    fraction * 100.0,
+                    downloaded,
+                    format_size(total as usize),
+                    speed
+                )
+            }
+            _ => format!("{} {} {}/s", self.file_name, downloaded, speed),
+        };
+        self.writer.write(&progress);
+    }
+
+    fn get_bar(&self, fraction: f64) -> String {
+        let width: usize = 30;
+        let filled: usize = (fraction * width as f64) as usize;
+        format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+    }
+
+    /// Bytes per second transferred in this session, excluding resumed bytes.
+    fn get_speed(&self) -> f64 {
+        let seconds: f64 = self.time.elapsed().as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        (self.downloaded - self.resumed) as f64 / seconds
+    }
+}
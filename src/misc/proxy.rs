@@ -0,0 +1,157 @@
+use std::env;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hyper::header;
+use hyper::header::HeaderValue;
+use hyper::Uri;
+use hyper_proxy::{Intercept, Proxy};
+
+/// Hosts excluded from proxying, in the comma separated `NO_PROXY` format.
+#[derive(Debug, Clone)]
+pub struct NoProxy {
+    entries: Vec<String>,
+}
+
+impl NoProxy {
+    pub fn from_string(string: &str) -> Self {
+        let entries: Vec<String> = string
+            .split(',')
+            .map(|entry| entry.trim().to_lowercase())
+            .filter(|entry| !entry.is_empty())
+            .collect();
+        NoProxy { entries }
+    }
+
+    /// Matches `*`, exact hosts, `host:port` and domain suffixes such as
+    /// `.example.com` or `example.com`, which both cover `www.example.com`.
+    pub fn matches(&self, host: &str, port: Option<u16>) -> bool {
+        let host: String = host.trim_matches(|c| c == '[' || c == ']').to_lowercase();
+        self.entries.iter().any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+
+            let (entry_host, entry_port): (&str, Option<u16>) = Self::split_port(entry);
+            if entry_port.is_some() && entry_port != port {
+                return false;
+            }
+
+            let domain: &str = entry_host.trim_start_matches('.');
+            host == domain || host.ends_with(&format!(".{}", domain))
+        })
+    }
+}
+
+impl NoProxy {
+    fn split_port(entry: &str) -> (&str, Option<u16>) {
+        if let Some((host, port)) = entry.rsplit_once(':') {
+            if let Ok(port) = port.parse::<u16>() {
+                return (host, Some(port));
+            }
+        }
+        (entry, None)
+    }
+}
+
+/// Proxy configuration read from `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and
+/// `NO_PROXY`, preferring the lowercase variables as curl does.
+#[derive(Debug, Clone)]
+pub struct ProxySettings {
+    http_proxy: Option<String>,
+    https_proxy: Option<String>,
+    no_proxy: NoProxy,
+}
+
+impl ProxySettings {
+    pub fn new(http_proxy: Option<&str>, https_proxy: Option<&str>, no_proxy: &str) -> Self {
+        let http_proxy: Option<String> = http_proxy.map(|proxy| proxy.to_string());
+        let https_proxy: Option<String> = https_proxy.map(|proxy| proxy.to_string());
+        let no_proxy: NoProxy = NoProxy::from_string(no_proxy);
+        ProxySettings {
+            http_proxy,
+            https_proxy,
+            no_proxy,
+        }
+    }
+
+    pub fn from_environment() -> Self {
+        let all_proxy: Option<String> = Self::get_variable("all_proxy");
+        let http_proxy: Option<String> = Self::get_variable("http_proxy").or(all_proxy.clone());
+        let https_proxy: Option<String> = Self::get_variable("https_proxy").or(all_proxy);
+        let no_proxy: String = Self::get_variable("no_proxy").unwrap_or_default();
+        Self::new(http_proxy.as_deref(), https_proxy.as_deref(), &no_proxy)
+    }
+
+    pub fn get_http_proxy(&self) -> Option<&str> {
+        self.http_proxy.as_deref()
+    }
+
+    pub fn get_https_proxy(&self) -> Option<&str> {
+        self.https_proxy.as_deref()
+    }
+
+    pub fn get_no_proxy(&self) -> &NoProxy {
+        &self.no_proxy
+    }
+
+    /// Builds one proxy per scheme, each skipping the hosts listed in `NO_PROXY`.
+    pub fn get_proxies(&self) -> Vec<Proxy> {
+        let mut proxies: Vec<Proxy> = Vec::new();
+        let schemes: [(&str, &Option<String>); 2] =
+            [("http", &self.http_proxy), ("https", &self.https_proxy)];
+
+        for (scheme, proxy_url) in schemes {
+            if let Some(proxy) = proxy_url
+                .as_deref()
+                .and_then(|url| self.get_proxy(scheme, url))
+            {
+                proxies.push(proxy);
+            }
+        }
+        proxies
+    }
+}
+
+impl ProxySettings {
+    fn get_variable(name: &str) -> Option<String> {
+        let value: Option<String> = env::var(name)
+            .ok()
+            .or_else(|| env::var(name.to_uppercase()).ok());
+        value.filter(|value| !value.trim().is_empty())
+    }
+
+    fn get_proxy(&self, scheme: &str, proxy_url: &str) -> Option<Proxy> {
+        let proxy_url: String = if proxy_url.contains("://") {
+            proxy_url.to_string()
+        } else {
+            format!("http://{}", proxy_url)
+        };
+        let uri: Uri = Uri::from_str(&proxy_url).ok()?;
+        let authority: &str = uri.authority()?.as_str();
+
+        // Credentials move into a Proxy-Authorization header, the connector only needs the address.
+        let (credentials, address): (Option<&str>, &str) = match authority.rsplit_once('@') {
+            Some((credentials, address)) => (Some(credentials), address),
+            None => (None, authority),
+        };
+        let proxy_scheme: &str = uri.scheme_str().unwrap_or("http");
+        let proxy_uri: Uri = Uri::from_str(&format!("{}://{}", proxy_scheme, address)).ok()?;
+
+        let target_scheme: String = scheme.to_string();
+        let no_proxy: NoProxy = self.no_proxy.clone();
+        let intercept = move |scheme: Option<&str>, host: Option<&str>, port: Option<u16>| {
+            let host: &str = host.unwrap_or_default();
+            scheme == Some(target_scheme.as_str()) && !no_proxy.matches(host, port)
+        };
+
+        let mut proxy: Proxy = Proxy::new(Intercept::from(intercept), proxy_uri);
+        if let Some(credentials) = credentials {
+            let encoded: String = STANDARD.encode(credentials);
+            let value: HeaderValue = HeaderValue::from_str(&format!("Basic {}", encoded)).ok()?;
+            proxy.set_header(header::PROXY_AUTHORIZATION, value);
+        }
+        Some(proxy)
+    }
+}
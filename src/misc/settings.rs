@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use dirs;
+
+use crate::parsers::cfg_parser::{CFGLine, CFGParser};
+
+/// User settings read from `<config dir>/arranger/settings.cfg`, one `name = value`
+/// per line. A missing file leaves every setting unset.
+pub struct Settings {
+    cfg_lines: Vec<CFGLine>,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        let cfg_lines: Vec<CFGLine> = Self::get_settings_path()
+            .and_then(|path| CFGParser::new().from_file(&path).ok())
+            .unwrap_or_default();
+        Settings { cfg_lines }
+    }
+
+    pub fn get_settings_path() -> Option<PathBuf> {
+        let config_dir: PathBuf = dirs::config_dir()?;
+        Some(config_dir.join("arranger").join("settings.cfg"))
+    }
+
+    pub fn get_setting(&self, name: &str) -> Option<&str> {
+        self.cfg_lines
+            .iter()
+            .rev()
+            .find(|cfg_line| cfg_line.get_name() == name)
+            .map(|cfg_line| cfg_line.get_setting())
+            .filter(|setting| !setting.is_empty())
+    }
+
+    /// Base URL of the Python download mirror, set with `python_mirror = <url>`.
+    pub fn get_python_mirror(&self) -> Option<&str> {
+        self.get_setting("python_mirror")
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
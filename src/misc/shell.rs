@@ -10,7 +10,7 @@ use std::thread;
 
 use super::path::WPath;
 use crate::terminal::Terminal;
-use crate::terminal::{GreenANSI, RedANSI, YellowANSI};
+use crate::terminal::{ANSICode, GreenANSI, RedANSI, YellowANSI};
 
 pub struct CommandResponse {
     stdout: String,
@@ -89,9 +89,28 @@ impl CommandExecute {
         None
     }
 
-    pub fn execute_spawn_command(&self, program: &WPath, args: &[&str]) {
-        let spawn: Result<Child, io::Error> = Command::new(program)
-            .args(args)
+    pub fn execute_spawn_command(&self, program: &WPath, args: &[&str]) -> Option<ExitStatus> {
+        let mut command: Command = Command::new(program);
+        command.args(args);
+        self.spawn_command(command)
+    }
+
+    /// Runs the command from `directory` instead of the current directory.
+    pub fn execute_spawn_command_in(
+        &self,
+        program: &WPath,
+        args: &[&str],
+        directory: &WPath,
+    ) -> Option<ExitStatus> {
+        let mut command: Command = Command::new(program);
+        command.args(args).current_dir(directory);
+        self.spawn_command(command)
+    }
+}
+
+impl CommandExecute {
+    fn spawn_command(&self, mut command: Command) -> Option<ExitStatus> {
+        let spawn: Result<Child, io::Error> = command
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn();
@@ -113,11 +132,11 @@ impl CommandExecute {
 
             let separator: String = "-".repeat(10);
             terminal.writeln_ansi(&separator, &YellowANSI);
+            return spawn.wait().ok();
         }
+        None
     }
-}
 
-impl CommandExecute {
     fn spawn_thread_for_io<T, F>(
         &self,
         stream: Option<T>,
@@ -134,44 +153,67 @@ impl CommandExecute {
         })
     }
 
-    fn write_stdout_buffer(mut stdout: ChildStdout) {
+    fn write_stdout_buffer(stdout: ChildStdout) {
+        Self::write_buffer(stdout, &GreenANSI);
+    }
+
+    fn write_stderr_buffer(stderr: ChildStderr) {
+        Self::write_buffer(stderr, &RedANSI);
+    }
+
+    /// Drains the stream until it closes, so the child never blocks on a full pipe.
+    /// A multibyte character split across reads is held back for the next chunk.
+    fn write_buffer<R: Read, T: ANSICode + 'static>(mut stream: R, ansi_code: &T) {
         let terminal: Terminal = Terminal::new();
         let mut buffer: [u8; 256] = [0; 256];
+        let mut pending: Vec<u8> = Vec::new();
+
+        loop {
+            let size: usize = match stream.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(size) => size,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    Self::write_spawn_error(Box::new(error), &terminal);
+                    break;
+                }
+            };
 
-        while let Ok(size) = stdout.read(&mut buffer) {
-            if size == 0 {
-                break;
-            }
+            pending.extend_from_slice(&buffer[..size]);
+            let length: usize = Self::get_decodable_length(&pending);
+            let string: String = String::from_utf8_lossy(&pending[..length]).to_string();
+            terminal.write_ansi(&string, ansi_code);
+            pending.drain(..length);
+        }
 
-            let string: Result<&str, str::Utf8Error> = str::from_utf8(&buffer[..size]);
-            if let Ok(string) = string {
-                terminal.write_ansi(string, &GreenANSI);
-            } else {
-                let error: str::Utf8Error = string.unwrap_err();
-                Self::write_spawn_error(Box::new(error), &terminal);
-                break;
-            }
+        if !pending.is_empty() {
+            let string: String = String::from_utf8_lossy(&pending).to_string();
+            terminal.write_ansi(&string, ansi_code);
         }
     }
 
-    fn write_stderr_buffer(mut stderr: ChildStderr) {
-        let terminal: Terminal = Terminal::new();
-        let mut buffer: [u8; 256] = [0; 256];
-
-        while let Ok(size) = stderr.read(&mut buffer) {
-            if size == 0 {
-                break;
+    /// Length of `bytes` that can be decoded now, excluding an incomplete UTF-8
+    /// character at the end.
+    fn get_decodable_length(bytes: &[u8]) -> usize {
+        let start: usize = bytes.len().saturating_sub(3);
+        for index in (start..bytes.len()).rev() {
+            let byte: u8 = bytes[index];
+            if byte & 0xC0 == 0x80 {
+                continue;
             }
 
-            let string: Result<&str, str::Utf8Error> = str::from_utf8(&buffer[..size]);
-            if let Ok(string) = string {
-                terminal.write_ansi(string, &RedANSI);
-            } else {
-                let error: str::Utf8Error = string.unwrap_err();
-                Self::write_spawn_error(Box::new(error), &terminal);
-                break;
+            let width: usize = match byte {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => 1,
+            };
+            if index + width > bytes.len() {
+                return index;
             }
+            break;
         }
+        bytes.len()
     }
 
     fn write_spawn_error(error: Box<dyn Error>, terminal: &Terminal) {
@@ -179,3 +221,4 @@ impl CommandExecute {
         terminal.writeln_ansi(&error_string, &RedANSI);
     }
 }
+
@@ -653,3 +653,220 @@ impl DynamicTable {
         (num * 10.0).floor() / 10.0
     }
 }
+
+pub struct GridCell {
+    text: String,
+    color: Option<Box<dyn ANSICode>>,
+}
+
+impl GridCell {
+    pub fn new(text: &str) -> GridCell {
+        let text: String = text.to_string();
+        GridCell { text, color: None }
+    }
+
+    pub fn new_colored<T: ANSICode + 'static>(text: &str, ansi: T) -> GridCell {
+        let text: String = text.to_string();
+        let color: Option<Box<dyn ANSICode>> = Some(ansi.boxed());
+        GridCell { text, color }
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// A table with a header row and any number of columns, where each cell may carry
+/// its own color.
+pub struct GridTable {
+    padding: usize,
+    header: String,
+    header_background_color: Box<dyn ANSICode>,
+    columns: Vec<String>,
+    rows: Vec<Vec<GridCell>>,
+}
+
+impl GridTable {
+    pub fn new(padding: usize) -> GridTable {
+        let header: String = String::new();
+        let header_background_color: Box<dyn ANSICode> = YellowBackgroundANSI.boxed();
+        let columns: Vec<String> = Vec::new();
+        let rows: Vec<Vec<GridCell>> = Vec::new();
+
+        GridTable {
+            padding,
+            header,
+            header_background_color,
+            columns,
+            rows,
+        }
+    }
+
+    pub fn set_header(&mut self, header: &str) {
+        self.header = header.to_string();
+    }
+
+    pub fn set_header_background_color<T: ANSICode + 'static>(&mut self, ansi: T) {
+        self.header_background_color = ansi.boxed();
+    }
+
+    pub fn set_columns(&mut self, columns: &[String]) {
+        self.columns = columns.to_vec();
+    }
+
+    /// Adds a row, padding or truncating it to the number of columns.
+    pub fn add_row(&mut self, mut row: Vec<GridCell>) {
+        row.truncate(self.columns.len());
+        while row.len() < self.columns.len() {
+            row.push(GridCell::new(""));
+        }
+        self.rows.push(row);
+    }
+
+    pub fn get_row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn get_string(&self) -> String {
+        let widths: Vec<usize> = self.compute_widths();
+        let width: usize = widths.iter().sum::<usize>() + widths.len() + 1;
+        let header: String = self.get_padded_header(width);
+        let header: String = self.get_header_ansi(&header);
+        let table: String = self.generate_table(&widths);
+        let string: String = format!("{}\n{}", header, table);
+        string
+    }
+
+    pub fn print(&self) {
+        let string: String = self.get_string();
+        println!("{}", string);
+    }
+}
+
+impl GridTable {
+    fn generate_table(&self, widths: &[usize]) -> String {
+        let mut lines: Vec<String> = Vec::new();
+
+        let top_line: String = self.get_border_line(
+            widths,
+            TableCharacter::TopLeft,
+            TableCharacter::TopT,
+            TableCharacter::TopRight,
+        );
+        lines.push(top_line);
+
+        let columns: Vec<GridCell> = self
+            .columns
+            .iter()
+            .map(|column| GridCell::new(column))
+            .collect();
+        lines.push(self.format_line(&columns, widths));
+
+        let separator_line: String = self.get_border_line(
+            widths,
+            TableCharacter::MidLeftT,
+            TableCharacter::MidT,
+            TableCharacter::MidRightT,
+        );
+        lines.push(separator_line);
+
+        for row in self.rows.iter() {
+            lines.push(self.format_line(row, widths));
+        }
+
+        let bottom_line: String = self.get_border_line(
+            widths,
+            TableCharacter::BottomLeft,
+            TableCharacter::BottomT,
+            TableCharacter::BottomRight,
+        );
+        lines.push(bottom_line);
+
+        let string: String = lines.join("\n");
+        string
+    }
+
+    fn compute_widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .map(|column| column.chars().count())
+            .collect();
+
+        for row in self.rows.iter() {
+            for (idx, cell) in row.iter().enumerate() {
+                widths[idx] = widths[idx].max(cell.text.chars().count());
+            }
+        }
+
+        widths
+            .into_iter()
+            .map(|width| width + 2 + (self.padding * 2))
+            .collect()
+    }
+
+    fn format_line(&self, cells: &[GridCell], widths: &[usize]) -> String {
+        let vert: &str = TableCharacter::Vertical.as_str();
+        let padding_spaces: String = " ".repeat(self.padding + 1);
+        let mut line: String = vert.to_string();
+
+        for (cell, width) in cells.iter().zip(widths.iter()) {
+            let text_width: usize = width - 2 - (self.padding * 2);
+            let text: String = format!("{:text_width$}", cell.text);
+            let text: String = match &cell.color {
+                Some(color) => color.value() + &text + &ResetANSI.value(),
+                None => text,
+            };
+            line.push_str(&padding_spaces);
+            line.push_str(&text);
+            line.push_str(&padding_spaces);
+            line.push_str(vert);
+        }
+        line
+    }
+
+    fn get_border_line(
+        &self,
+        widths: &[usize],
+        left: TableCharacter,
+        middle: TableCharacter,
+        right: TableCharacter,
+    ) -> String {
+        let horizontals: Vec<String> = widths
+            .iter()
+            .map(|width| TableCharacter::Horizontal.as_str().repeat(*width))
+            .collect();
+
+        let border_line: String = format!(
+            "{}{}{}",
+            left.as_str(),
+            horizontals.join(middle.as_str()),
+            right.as_str()
+        );
+        border_line
+    }
+
+    fn get_padded_header(&self, width: usize) -> String {
+        let header_length: usize = self.header.chars().count();
+        if header_length >= width {
+            return self.header.clone();
+        }
+
+        let padding_length: usize = (width - header_length) / 2;
+        let residual_length: usize = width - header_length - (padding_length * 2);
+        let padded_header: String = format!(
+            "{}{}{}",
+            " ".repeat(padding_length),
+            self.header,
+            " ".repeat(padding_length + residual_length)
+        );
+        padded_header
+    }
+
+    fn get_header_ansi(&self, header: &str) -> String {
+        let ansi: String = self.header_background_color.combine(&BlackANSI).value();
+        let reset_ansi: String = ResetANSI.value();
+        let ansi_header: String = ansi + header + &reset_ansi;
+        ansi_header
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::Duration;
+
+use super::https::HTTPSConfig;
+use super::proxy::ProxySettings;
+
+/// Answers each connection on a loopback port with the bytes `respond` returns for its
+/// request head, then closes it. A response shorter than its Content-Length therefore
+/// drops the connection mid-body. Returns the base URL and the request heads received.
+pub fn serve<F>(mut respond: F) -> (String, Receiver<String>)
+where
+    F: FnMut(&str) -> Vec<u8> + Send + 'static,
+{
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url: String = format!("http://{}/", listener.local_addr().unwrap());
+    let (sender, receiver): (mpsc::Sender<String>, Receiver<String>) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let request: String = read_request(&stream);
+            let response: Vec<u8> = respond(&request);
+            let _ = sender.send(request);
+            let mut stream: TcpStream = stream;
+            let _ = stream.write_all(&response);
+        }
+    });
+    (base_url, receiver)
+}
+
+/// Returns the path of a request head such as `GET /3.12.0/ HTTP/1.1`.
+pub fn get_request_path(request: &str) -> &str {
+    request.split_whitespace().nth(1).unwrap_or("/")
+}
+
+/// Builds a raw response that announces `length` bytes, whatever the body holds.
+pub fn get_response(status: &str, headers: &[&str], body: &[u8], length: usize) -> Vec<u8> {
+    let mut head: String = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\n", status, length);
+    for header in headers {
+        head.push_str(&format!("{}\r\n", header));
+    }
+    head.push_str("Connection: close\r\n\r\n");
+
+    let mut response: Vec<u8> = head.into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+/// Retries without waiting and ignores proxy variables, which would otherwise route
+/// requests for the loopback server elsewhere.
+pub fn get_https_config() -> HTTPSConfig {
+    let mut config: HTTPSConfig = HTTPSConfig::new();
+    config.set_retry_delay(Duration::from_millis(1));
+    config.set_proxy(ProxySettings::new(None, None, ""));
+    config
+}
+
+/// Returns a per-process path in the temp directory, e.g. `arranger-https-<pid>-<name>`.
+pub fn get_temp_path(area: &str, name: &str) -> PathBuf {
+    let file_name: String = format!("arranger-{}-{}-{}", area, process::id(), name);
+    std::env::temp_dir().join(file_name)
+}
+
+fn read_request(stream: &TcpStream) -> String {
+    let mut reader: BufReader<&TcpStream> = BufReader::new(stream);
+    let mut request: String = String::new();
+    loop {
+        let mut line: String = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+            break;
+        }
+        request.push_str(&line);
+    }
+    request
+}
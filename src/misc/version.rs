@@ -30,8 +30,12 @@ impl PreRelease {
     }
 
     pub fn get_string(&self) -> String {
-        let character: char = char::from(self.release_type);
-        let string: String = format!("{}{}", character, self.version);
+        // Release candidates are stored by their first letter, as are alpha and beta.
+        let release_type: String = match self.release_type {
+            b'r' => "rc".to_string(),
+            release_type => char::from(release_type).to_string(),
+        };
+        let string: String = format!("{}{}", release_type, self.version);
         string
     }
 }
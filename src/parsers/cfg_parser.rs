@@ -2,6 +2,7 @@ use std::fmt::Debug;
 use std::io::Error;
 use std::path::PathBuf;
 
+#[derive(Clone)]
 pub struct CFGLine {
     name: String,
     setting: String,
@@ -19,6 +20,15 @@ impl CFGLine {
     pub fn get_setting(&self) -> &str {
         &self.setting
     }
+
+    pub fn set_setting(&mut self, setting: String) {
+        self.setting = setting;
+    }
+
+    pub fn get_line(&self) -> String {
+        let line: String = format!("{} = {}", self.name, self.setting);
+        line
+    }
 }
 
 impl Debug for CFGLine {
@@ -54,28 +64,19 @@ impl CFGParser {
         cfg_vec
     }
 
-    fn parse_line(&self, line: &str) -> Option<(String, String)> {
-        let mut name_vec: Vec<char> = Vec::new();
-        let mut setting_vec: Vec<char> = Vec::new();
-
-        let mut vector: &mut Vec<char> = &mut name_vec;
-        let mut shift: bool = false;
-        for char in line.chars() {
-            if char == '=' {
-                vector = &mut setting_vec;
-                shift = true;
-                continue;
-            }
-
-            if !char.is_ascii_whitespace() {
-                vector.push(char);
-            }
-        }
-        if shift == true {
-            let name: String = name_vec.into_iter().collect();
-            let setting: String = setting_vec.into_iter().collect();
-            return Some((name, setting));
+    pub fn to_content(&self, cfg_lines: &[CFGLine]) -> String {
+        let mut content: String = String::new();
+        for cfg_line in cfg_lines {
+            content.push_str(&cfg_line.get_line());
+            content.push('\n');
         }
-        None
+        content
+    }
+
+    fn parse_line(&self, line: &str) -> Option<(String, String)> {
+        let (name, setting): (&str, &str) = line.split_once('=')?;
+        let name: String = name.trim().to_string();
+        let setting: String = setting.trim().to_string();
+        Some((name, setting))
     }
 }
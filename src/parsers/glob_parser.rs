@@ -0,0 +1,76 @@
+use regex::Regex;
+
+pub struct GlobPattern {
+    pattern: String,
+    regex: Regex,
+}
+
+impl GlobPattern {
+    /// Compiles a glob where `*` and `?` stop at path separators, `**` crosses them
+    /// and `[...]` matches a character class.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        let regex_string: String = Self::to_regex_string(pattern);
+        let regex: Regex = Regex::new(&regex_string)?;
+        let pattern: String = pattern.to_string();
+        Ok(GlobPattern { pattern, regex })
+    }
+
+    pub fn get_pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        let path: String = path.replace('\\', "/");
+        self.regex.is_match(&path)
+    }
+}
+
+impl GlobPattern {
+    fn to_regex_string(pattern: &str) -> String {
+        let pattern: String = pattern.replace('\\', "/");
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut regex_string: String = String::from("^");
+        let mut idx: usize = 0;
+
+        while idx < chars.len() {
+            let char: char = chars[idx];
+            match char {
+                '*' if chars.get(idx + 1) == Some(&'*') => {
+                    // A "**/" segment may also match zero directories.
+                    if chars.get(idx + 2) == Some(&'/') {
+                        regex_string.push_str("(?:.*/)?");
+                        idx += 3;
+                    } else {
+                        regex_string.push_str(".*");
+                        idx += 2;
+                    }
+                    continue;
+                }
+                '*' => regex_string.push_str("[^/]*"),
+                '?' => regex_string.push_str("[^/]"),
+                '[' => {
+                    let close: Option<usize> = chars[idx + 1..].iter().position(|&c| c == ']');
+                    if let Some(close) = close {
+                        let class: String = chars[idx + 1..idx + 1 + close].iter().collect();
+                        let class: String = match class.strip_prefix('!') {
+                            Some(negated) => format!("^{}", negated),
+                            None => class,
+                        };
+                        regex_string.push_str(&format!("[{}]", class.replace('\\', "\\\\")));
+                        idx += close + 2;
+                        continue;
+                    }
+                    regex_string.push_str("\\[");
+                }
+                char => regex_string.push_str(&regex::escape(&char.to_string())),
+            }
+            idx += 1;
+        }
+
+        regex_string.push('$');
+        if cfg!(windows) {
+            regex_string.insert_str(0, "(?i)");
+        }
+        regex_string
+    }
+}
@@ -1 +1,3 @@
-pub mod cfg_parser;
\ No newline at end of file
+pub mod cfg_parser;
+pub mod glob_parser;
+pub mod shell_parser;
@@ -0,0 +1,112 @@
+use std::io;
+use std::iter::Peekable;
+use std::str::Chars;
+
+enum QuoteState {
+    Unquoted,
+    Single,
+    Double,
+}
+
+pub struct ShellParser;
+
+impl ShellParser {
+    pub fn new() -> Self {
+        ShellParser
+    }
+
+    /// Splits a command line into arguments following POSIX shell quoting rules.
+    ///
+    /// Outside of quotes a backslash only escapes whitespace, quotes and itself so
+    /// Windows paths such as `C:\Scripts\run.py` pass through untouched.
+    pub fn tokenize(&self, text: &str) -> Result<Vec<String>, io::Error> {
+        let mut tokens: Vec<String> = Vec::new();
+        let mut token: String = String::new();
+        let mut in_token: bool = false;
+        let mut state: QuoteState = QuoteState::Unquoted;
+        let mut chars: Peekable<Chars> = text.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            match state {
+                QuoteState::Unquoted => match char {
+                    '\'' => {
+                        state = QuoteState::Single;
+                        in_token = true;
+                    }
+                    '"' => {
+                        state = QuoteState::Double;
+                        in_token = true;
+                    }
+                    '\\' => {
+                        let escaped: char = self.get_escaped(&mut chars, |next| {
+                            next.is_whitespace() || matches!(next, '\'' | '"' | '\\')
+                        });
+                        token.push(escaped);
+                        in_token = true;
+                    }
+                    char if char.is_whitespace() => {
+                        if in_token {
+                            tokens.push(std::mem::take(&mut token));
+                            in_token = false;
+                        }
+                    }
+                    char => {
+                        token.push(char);
+                        in_token = true;
+                    }
+                },
+                QuoteState::Single => match char {
+                    '\'' => state = QuoteState::Unquoted,
+                    char => token.push(char),
+                },
+                QuoteState::Double => match char {
+                    '"' => state = QuoteState::Unquoted,
+                    '\\' => {
+                        let escaped: char = self
+                            .get_escaped(&mut chars, |next| matches!(next, '"' | '\\' | '$' | '`'));
+                        token.push(escaped);
+                    }
+                    char => token.push(char),
+                },
+            }
+        }
+
+        match state {
+            QuoteState::Unquoted => {
+                if in_token {
+                    tokens.push(token);
+                }
+                Ok(tokens)
+            }
+            QuoteState::Single => Err(self.unterminated_error("single")),
+            QuoteState::Double => Err(self.unterminated_error("double")),
+        }
+    }
+}
+
+impl ShellParser {
+    fn get_escaped<F>(&self, chars: &mut Peekable<Chars>, is_escapable: F) -> char
+    where
+        F: Fn(char) -> bool,
+    {
+        let next: Option<char> = chars.peek().copied();
+        if let Some(next) = next {
+            if is_escapable(next) {
+                chars.next();
+                return next;
+            }
+        }
+        '\\'
+    }
+
+    fn unterminated_error(&self, quote: &str) -> io::Error {
+        let error_string: String = format!("Unterminated {} quote in command", quote);
+        io::Error::new(io::ErrorKind::InvalidInput, error_string)
+    }
+}
+
+impl Default for ShellParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
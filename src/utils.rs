@@ -41,6 +41,84 @@ impl ConfirmationPrompt {
     }
 }
 
+pub struct SelectionPrompt;
+
+impl SelectionPrompt {
+    /// Reads a selection such as `1,3-5` or `all` and returns zero-based indices.
+    pub fn prompt(terminal: &Terminal, count: usize) -> Vec<usize> {
+        let mut input: String = String::new();
+
+        let string: &str = "\nSelect environments [e.g. 1,3-5 or all]: ";
+        terminal.write_ansi(string, &CyanANSI);
+
+        match io::stdin().read_line(&mut input) {
+            Ok(_) => Self::process_input(terminal, &input, count),
+            Err(error) => {
+                let string: String = format!("Failed to read line: {}\n", error);
+                terminal.writeln_ansi(&string, &RedANSI);
+                Vec::new()
+            }
+        }
+    }
+}
+
+impl SelectionPrompt {
+    fn process_input(terminal: &Terminal, input: &str, count: usize) -> Vec<usize> {
+        let selection: Option<Vec<usize>> = Self::parse_selection(input, count);
+
+        match selection {
+            Some(selection) if !selection.is_empty() => {
+                let string: String = format!("Selected {} environment(s)...\n\n", selection.len());
+                terminal.writeln_ansi(&string, &GreenANSI);
+                selection
+            }
+            Some(_) => {
+                let string: &str = "Nothing selected, not continuing...\n";
+                terminal.writeln_ansi(string, &RedANSI);
+                Vec::new()
+            }
+            None => {
+                let string: String = format!("Invalid selection: {}\n", input.trim());
+                terminal.writeln_ansi(&string, &RedANSI);
+                Vec::new()
+            }
+        }
+    }
+
+    fn parse_selection(input: &str, count: usize) -> Option<Vec<usize>> {
+        let input: &str = input.trim();
+        if input.eq_ignore_ascii_case("all") || input.eq_ignore_ascii_case("a") {
+            return Some((0..count).collect());
+        }
+
+        let mut selection: Vec<usize> = Vec::new();
+        for part in input
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let (start, end): (usize, usize) = match part.split_once('-') {
+                Some((start, end)) => (start.trim().parse().ok()?, end.trim().parse().ok()?),
+                None => {
+                    let number: usize = part.parse().ok()?;
+                    (number, number)
+                }
+            };
+
+            if start == 0 || end > count || start > end {
+                return None;
+            }
+
+            for number in start..=end {
+                if !selection.contains(&(number - 1)) {
+                    selection.push(number - 1);
+                }
+            }
+        }
+        Some(selection)
+    }
+}
+
 pub struct StringOp;
 
 impl StringOp {